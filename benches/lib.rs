@@ -4,7 +4,21 @@ extern crate rulinalg;
 extern crate test;
 extern crate rand;
 
+use rand::{Rng, SeedableRng};
+use rulinalg::matrix::Matrix;
+
 mod linalg {
+	mod eigen;
+	mod lu;
 	mod matrix;
 	mod svd;
-}
\ No newline at end of file
+}
+
+/// A matrix with uniformly distributed entries in [0, 1), generated
+/// from a fixed seed so that every run benchmarks the same input.
+fn reproducible_random_matrix(rows: usize, cols: usize) -> Matrix<f64> {
+	const STANDARD_SEED: [usize; 4] = [12, 2049, 4000, 33];
+	let mut rng = rand::StdRng::from_seed(&STANDARD_SEED);
+	let elements: Vec<_> = rng.gen_iter::<f64>().take(rows * cols).collect();
+	Matrix::new(rows, cols, elements)
+}
@@ -0,0 +1,26 @@
+use test::Bencher;
+use rulinalg::matrix::{Matrix, BaseMatrix};
+use reproducible_random_matrix;
+
+fn reproducible_random_symmetric_matrix(size: usize) -> Matrix<f64> {
+    let mat = reproducible_random_matrix(size, size);
+    &mat + mat.transpose()
+}
+
+#[bench]
+fn eigendecomp_50_50(b: &mut Bencher) {
+    let mat = reproducible_random_symmetric_matrix(50);
+
+    b.iter(||
+        mat.eigendecomp()
+    )
+}
+
+#[bench]
+fn symmetric_eigendecomp_50_50(b: &mut Bencher) {
+    let mat = reproducible_random_symmetric_matrix(50);
+
+    b.iter(||
+        mat.symmetric_eigendecomp()
+    )
+}
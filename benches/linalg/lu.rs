@@ -0,0 +1,57 @@
+use test::Bencher;
+use rulinalg::matrix::decomposition::PartialPivLu;
+use reproducible_random_matrix;
+
+#[bench]
+fn lup_decomp_256_256(b: &mut Bencher) {
+    let mat = reproducible_random_matrix(256, 256);
+
+    b.iter(||
+        mat.lup_decomp()
+    )
+}
+
+#[bench]
+fn lup_decomp_512_512(b: &mut Bencher) {
+    let mat = reproducible_random_matrix(512, 512);
+
+    b.iter(||
+        mat.lup_decomp()
+    )
+}
+
+#[bench]
+fn lup_decomp_1024_1024(b: &mut Bencher) {
+    let mat = reproducible_random_matrix(1024, 1024);
+
+    b.iter(||
+        mat.lup_decomp()
+    )
+}
+
+#[bench]
+fn partial_piv_lu_256_256(b: &mut Bencher) {
+    let mat = reproducible_random_matrix(256, 256);
+
+    b.iter(||
+        PartialPivLu::decompose(mat.clone())
+    )
+}
+
+#[bench]
+fn partial_piv_lu_512_512(b: &mut Bencher) {
+    let mat = reproducible_random_matrix(512, 512);
+
+    b.iter(||
+        PartialPivLu::decompose(mat.clone())
+    )
+}
+
+#[bench]
+fn partial_piv_lu_1024_1024(b: &mut Bencher) {
+    let mat = reproducible_random_matrix(1024, 1024);
+
+    b.iter(||
+        PartialPivLu::decompose(mat.clone())
+    )
+}
@@ -19,6 +19,8 @@
 //! - Cholesky decomposition
 //! - Eigenvalue decomposition
 //! - Upper Hessenberg decomposition
+//! - Real Schur decomposition
+//! - Generalized eigenvalue problems
 //! - Linear system solver
 //! - Other standard transformations, e.g. Transposing, concatenation, etc.
 //!
@@ -87,6 +89,9 @@
 #![warn(missing_debug_implementations)]
 
 extern crate num as libnum;
+extern crate num_complex;
+#[cfg(feature = "rand")]
+extern crate rand;
 extern crate matrixmultiply;
 
 // macros should be at the top in order for macros to be accessible in subsequent modules
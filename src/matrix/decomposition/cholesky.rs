@@ -0,0 +1,509 @@
+//! Cholesky decomposition of symmetric positive definite matrices.
+//!
+//! The lower triangular factor L is computed once, and can then be reused
+//! to solve systems, or to compute the inverse and determinant.
+//!
+//! References:
+//! 1. Matrix Computations, Golub and Van Loan, Section 4.2.
+
+use std::any::Any;
+use std::cmp;
+
+use matrix::{Matrix, BaseMatrix, BaseMatrixMut};
+use matrix::{forward_substitution_in_place, forward_substitution_transpose_in_place};
+use vector::Vector;
+use error::{Error, ErrorKind};
+use utils;
+
+use libnum::Float;
+
+use super::{cholesky_in_place, givens_rot, inverse_norm_estimate};
+
+/// The Cholesky decomposition of a symmetric positive definite matrix.
+///
+/// The decomposition is A = LL<sup>T</sup>, where L is lower triangular
+/// with a positive diagonal.
+///
+/// # Examples
+///
+/// ```
+/// use rulinalg::matrix::Matrix;
+/// use rulinalg::matrix::decomposition::Cholesky;
+/// use rulinalg::vector::Vector;
+///
+/// let a = Matrix::new(3, 3, vec![4.0f64, 2.0, -2.0,
+///                                2.0, 10.0, 2.0,
+///                                -2.0, 2.0, 5.0]);
+///
+/// let cholesky = Cholesky::decompose(a);
+///
+/// let x = cholesky.solve(Vector::new(vec![4.0, 14.0, 5.0])).unwrap();
+/// assert!(x.data().iter().all(|x_i| (x_i - 1.0).abs() < 1e-12));
+///
+/// assert!((cholesky.det() - 108.0).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cholesky<T> {
+    l: Matrix<T>,
+    // The 1-norm of the decomposed matrix, used by `rcond`. This is
+    // unknown after an update or downdate.
+    norm: Option<T>,
+}
+
+impl<T: Any + Float> Cholesky<T> {
+    /// Computes the Cholesky decomposition of the matrix.
+    ///
+    /// Only the lower triangle of the matrix is used, the matrix
+    /// is assumed to be symmetric. The matrix is consumed and its
+    /// storage is reused for the factor L.
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    /// - The matrix is not positive definite, see `try_decompose`.
+    pub fn decompose(matrix: Matrix<T>) -> Cholesky<T> {
+        match Cholesky::try_decompose(matrix) {
+            Ok(cholesky) => cholesky,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Computes the Cholesky decomposition of the matrix, failing if
+    /// the matrix is not positive definite.
+    ///
+    /// The error message gives the index of the first pivot which
+    /// is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::Cholesky;
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 1.0]);
+    ///
+    /// let err = Cholesky::try_decompose(a).unwrap_err();
+    /// assert_eq!(err.to_string(), "Matrix is not positive definite, pivot 1 is not positive.");
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is not positive definite.
+    pub fn try_decompose(matrix: Matrix<T>) -> Result<Cholesky<T>, Error> {
+        let n = matrix.rows();
+        assert!(n == matrix.cols(),
+                "Matrix must be square for Cholesky decomposition.");
+
+        // The 1-norm of the symmetric matrix, using only the lower triangle.
+        let norm = (0..n).fold(T::zero(), |acc, j| {
+            let upper = (0..j).fold(T::zero(), |sum, k| sum + matrix[[j, k]].abs());
+            acc.max((j..n).fold(upper, |sum, i| sum + matrix[[i, j]].abs()))
+        });
+
+        let mut l = matrix;
+        if let Err(j) = cholesky_in_place(&mut l.as_mut_slice()) {
+            return Err(Error::new(ErrorKind::DecompFailure,
+                                  format!("Matrix is not positive definite, pivot {} is not \
+                                           positive.",
+                                          j)));
+        }
+
+        Ok(Cholesky {
+            l: l,
+            norm: Some(norm),
+        })
+    }
+
+    /// The lower triangular factor L.
+    pub fn l(&self) -> &Matrix<T> {
+        &self.l
+    }
+
+    /// Consumes the decomposition and returns the factor L.
+    pub fn into_l(self) -> Matrix<T> {
+        self.l
+    }
+
+    /// Solves the equation `Ax = b`.
+    ///
+    /// # Panics
+    ///
+    /// - The size of `b` does not match the size of the matrix.
+    pub fn solve(&self, b: Vector<T>) -> Result<Vector<T>, Error> {
+        let size = b.size();
+        let x = try!(self.solve_matrix(Matrix::new(size, 1, b.into_vec())));
+        Ok(Vector::new(x.into_vec()))
+    }
+
+    /// Solves the equation `AX = B` for all columns of `B` at once.
+    ///
+    /// The decomposition always succeeds in solving the system,
+    /// the `Result` is kept for consistency with the other solvers.
+    ///
+    /// # Panics
+    ///
+    /// - The number of rows of `b` does not match the size of the matrix.
+    pub fn solve_matrix(&self, b: Matrix<T>) -> Result<Matrix<T>, Error> {
+        assert!(b.rows() == self.l.rows(),
+                "Right-hand side must have the same number of rows as the matrix.");
+
+        Ok(self.solve_unchecked(b))
+    }
+
+    /// Solves the equation `Aᵀx = b`.
+    ///
+    /// The decomposed matrix is symmetric, so this is the same as `solve`.
+    /// It is provided so that code written against the other decompositions
+    /// can use a Cholesky decomposition unchanged.
+    ///
+    /// # Panics
+    ///
+    /// - The size of `b` does not match the size of the matrix.
+    pub fn solve_transpose(&self, b: Vector<T>) -> Result<Vector<T>, Error> {
+        self.solve(b)
+    }
+
+    /// Solves `AX = B`, the right-hand side must have the correct size.
+    fn solve_unchecked(&self, b: Matrix<T>) -> Matrix<T> {
+        let mut x = b;
+
+        // Solve LY = B, then LᵀX = Y.
+        forward_substitution_in_place(&self.l, &mut x, false);
+        forward_substitution_transpose_in_place(&self.l, &mut x, false);
+
+        x
+    }
+
+    /// Updates the decomposition of A to the decomposition of
+    /// A + vv<sup>T</sup>.
+    ///
+    /// The factor is modified with a sequence of Givens rotations,
+    /// which takes O(n<sup>2</sup>) operations. The 1-norm of the updated
+    /// matrix is not known afterwards, so the next call to `rcond` has to
+    /// form LL<sup>T</sup>, which takes O(n<sup>3</sup>) operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::Cholesky;
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let mut cholesky = Cholesky::decompose(Matrix::new(2, 2, vec![4.0, 2.0, 2.0, 5.0]));
+    /// cholesky.update(Vector::new(vec![1.0, 2.0]));
+    ///
+    /// let expected = Cholesky::decompose(Matrix::new(2, 2, vec![5.0, 4.0, 4.0, 9.0]));
+    /// let diff = cholesky.l() - expected.l();
+    /// assert!(diff.data().iter().all(|x: &f64| x.abs() < 1e-12));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The size of `v` does not match the size of the matrix.
+    pub fn update(&mut self, v: Vector<T>) {
+        let n = self.l.rows();
+        assert!(v.size() == n, "Vector must have the same size as the matrix.");
+
+        let mut v = v.into_vec();
+        self.norm = None;
+
+        for k in 0..n {
+            // Rotate row k of Lᵀ against v to zero out v[k].
+            let (c, s) = givens_rot(self.l[[k, k]], v[k]);
+
+            for (i, v_i) in v.iter_mut().enumerate().skip(k) {
+                let l_ik = self.l[[i, k]];
+                self.l[[i, k]] = c * l_ik + s * *v_i;
+                *v_i = c * *v_i - s * l_ik;
+            }
+        }
+    }
+
+    /// Updates the decomposition of A to the decomposition of
+    /// A - vv<sup>T</sup>.
+    ///
+    /// This uses the LINPACK downdating algorithm, which takes
+    /// O(n<sup>2</sup>) operations. As with `update`, the next call to
+    /// `rcond` then takes O(n<sup>3</sup>) operations.
+    ///
+    /// # Panics
+    ///
+    /// - The size of `v` does not match the size of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - A - vv<sup>T</sup> is not positive definite, the decomposition is unchanged.
+    pub fn downdate(&mut self, v: Vector<T>) -> Result<(), Error> {
+        let n = self.l.rows();
+        assert!(v.size() == n, "Vector must have the same size as the matrix.");
+
+        // Solve L p = v, A - vvᵀ is positive definite if and only if |p| < 1.
+        let mut p = v.into_vec();
+        for i in 0..n {
+            let sum = utils::dot(&self.l.data[i * n..i * n + i], &p[..i]);
+            p[i] = (p[i] - sum) / self.l[[i, i]];
+        }
+
+        let rho_squared = T::one() - utils::dot(&p, &p);
+        if rho_squared <= T::zero() || !rho_squared.is_finite() {
+            return Err(Error::new(ErrorKind::DecompFailure,
+                                  "Downdate would make the matrix not positive definite."));
+        }
+
+        // The rotations which reduce [p; rho] to a multiple of the last unit vector.
+        let mut rho = rho_squared.sqrt();
+        let mut rotations = vec![(T::one(), T::zero()); n];
+        for i in (0..n).rev() {
+            rotations[i] = givens_rot(rho, p[i]);
+            rho = rho.hypot(p[i]);
+        }
+
+        self.norm = None;
+
+        // Apply the rotations to the rows of Lᵀ.
+        for j in 0..n {
+            let mut x = T::zero();
+            for i in (0..j + 1).rev() {
+                let (c, s) = rotations[i];
+                let l_ji = self.l[[j, i]];
+                self.l[[j, i]] = c * l_ji - s * x;
+                x = c * x + s * l_ji;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Computes the inverse of the decomposed matrix.
+    pub fn inverse(&self) -> Result<Matrix<T>, Error> {
+        self.solve_matrix(Matrix::identity(self.l.rows()))
+    }
+
+    /// Computes the determinant of the decomposed matrix.
+    ///
+    /// This may overflow for large matrices, in which case
+    /// `log_det` should be used instead.
+    pub fn det(&self) -> T {
+        let d = (0..self.l.rows()).fold(T::one(), |acc, i| acc * self.l[[i, i]]);
+        d * d
+    }
+
+    /// Computes the natural logarithm of the determinant of the
+    /// decomposed matrix.
+    pub fn log_det(&self) -> T {
+        let two = T::one() + T::one();
+        (0..self.l.rows()).fold(T::zero(), |acc, i| acc + two * self.l[[i, i]].ln())
+    }
+
+    /// Estimates the reciprocal of the condition number of the matrix
+    /// in the 1-norm.
+    ///
+    /// The norm of the inverse is estimated from a few solves with the
+    /// existing decomposition, as in `PartialPivLu::rcond`, which takes
+    /// O(n<sup>2</sup>) operations. The norm of the matrix is recorded by
+    /// the decomposition, but after an `update` or `downdate` it is
+    /// recomputed from the factor on every call, which takes
+    /// O(n<sup>3</sup>) operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::Cholesky;
+    ///
+    /// let a = Matrix::new(2, 2, vec![2.0f64, 0.0, 0.0, 1e-8]);
+    /// let rcond = Cholesky::decompose(a).rcond();
+    ///
+    /// assert!((rcond - 5e-9).abs() < 1e-20);
+    /// ```
+    pub fn rcond(&self) -> T {
+        let n = self.l.rows();
+        if n == 0 {
+            return T::one();
+        }
+
+        let norm = match self.norm {
+            Some(norm) => norm,
+            None => self.reconstructed_norm(),
+        };
+
+        // The matrix is symmetric, so solving with A and Aᵀ is the same.
+        let solve = |x| self.solve_unchecked(Matrix::new(n, 1, x)).into_vec();
+        let inverse_norm = inverse_norm_estimate(n, solve, solve);
+
+        T::one() / (norm * inverse_norm)
+    }
+
+    /// Computes the 1-norm of LL<sup>T</sup>.
+    fn reconstructed_norm(&self) -> T {
+        let n = self.l.rows();
+
+        (0..n).fold(T::zero(), |acc, j| {
+            let column_norm = (0..n).fold(T::zero(), |sum, i| {
+                let k = cmp::min(i, j) + 1;
+                let a_ij = utils::dot(&self.l.data[i * n..i * n + k], &self.l.data[j * n..j * n + k]);
+                sum + a_ij.abs()
+            });
+            acc.max(column_norm)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use matrix::decomposition::tests::assert_close;
+    use vector::Vector;
+
+    use super::Cholesky;
+
+    fn test_matrix() -> Matrix<f64> {
+        Matrix::new(4,
+                    4,
+                    vec![6.0, 2.0, 1.0, -1.0, 2.0, 5.0, 0.5, 1.0, 1.0, 0.5, 4.0, 0.0, -1.0, 1.0,
+                         0.0, 3.0])
+    }
+
+    #[test]
+    fn test_cholesky_factor() {
+        let a = test_matrix();
+        let cholesky = Cholesky::decompose(a.clone());
+        let l = cholesky.l();
+
+        for i in 0..4 {
+            assert!(l[[i, i]] > 0.0);
+            for j in i + 1..4 {
+                assert_eq!(l[[i, j]], 0.0);
+            }
+        }
+
+        assert_close(&(l * l.transpose()), &a, 1e-12);
+        assert_close(cholesky.l(), &a.cholesky().unwrap(), 1e-12);
+    }
+
+    #[test]
+    fn test_cholesky_solve() {
+        let a = test_matrix();
+        let cholesky = Cholesky::decompose(a.clone());
+
+        let b = Vector::new(vec![1.0, -2.0, 3.0, 0.5]);
+        let x = cholesky.solve(b.clone()).unwrap();
+        let residual = &a * x - b;
+        assert!(residual.data().iter().all(|r| r.abs() < 1e-12));
+
+        let b = Matrix::new(4, 2, vec![1.0, 0.0, 2.0, -1.0, 3.0, 5.0, 4.0, 0.5]);
+        let x = cholesky.solve_matrix(b.clone()).unwrap();
+        assert_close(&(&a * x), &b, 1e-12);
+
+        let inv = cholesky.inverse().unwrap();
+        assert_close(&(&a * inv), &Matrix::identity(4), 1e-12);
+
+        let b = Vector::new(vec![1.0, -2.0, 3.0, 0.5]);
+        assert_eq!(cholesky.solve_transpose(b.clone()).unwrap(), cholesky.solve(b).unwrap());
+    }
+
+    #[test]
+    fn test_cholesky_solve_matrix_blocked() {
+        // Large enough to use several blocks in the triangular solves.
+        let n = 150;
+        let a = Matrix::from_fn(n, n, |col, row| {
+            if row == col {
+                n as f64
+            } else {
+                1.0 / (1.0 + (row + col) as f64)
+            }
+        });
+        let b = Matrix::from_fn(n, 3, |col, row| (row * 3 + col) as f64 - 200.0);
+
+        let x = Cholesky::decompose(a.clone()).solve_matrix(b.clone()).unwrap();
+        assert_close(&(&a * x), &b, 1e-10);
+    }
+
+    #[test]
+    fn test_cholesky_det() {
+        let a = test_matrix();
+        let det = a.det();
+        let cholesky = Cholesky::decompose(a);
+
+        assert!((cholesky.det() - det).abs() < 1e-10);
+        assert!((cholesky.log_det() - det.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cholesky_not_positive_definite() {
+        let a = Matrix::new(3, 3, vec![4.0, 2.0, 0.0, 2.0, 1.0, 0.0, 0.0, 0.0, 1.0]);
+        let err = Cholesky::try_decompose(a).unwrap_err();
+
+        assert_eq!(err.to_string(),
+                   "Matrix is not positive definite, pivot 1 is not positive.");
+    }
+
+    #[test]
+    fn test_cholesky_rcond() {
+        let a = Matrix::new(3, 3, vec![4.0, 2.0, -2.0, 2.0, 10.0, 2.0, -2.0, 2.0, 5.0]);
+        let inv = a.clone().inverse().unwrap();
+        let one_norm = |m: &Matrix<f64>| {
+            (0..3).fold(0.0, |acc: f64, j| acc.max((0..3).map(|i| m[[i, j]].abs()).sum()))
+        };
+        let exact = 1.0 / (one_norm(&a) * one_norm(&inv));
+
+        let mut cholesky = Cholesky::decompose(a);
+        let estimate = cholesky.rcond();
+        assert!(estimate >= exact * (1.0 - 1e-12));
+        assert!(estimate <= 10.0 * exact);
+
+        // After an update the norm is recomputed from the factor.
+        cholesky.update(Vector::new(vec![1.0, 2.0, 0.0]));
+        let updated = Matrix::new(3, 3, vec![5.0, 4.0, -2.0, 4.0, 14.0, 2.0, -2.0, 2.0, 5.0]);
+        let expected = Cholesky::decompose(updated).rcond();
+        assert!((cholesky.rcond() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_cholesky_update() {
+        let a = test_matrix();
+        let v = Vector::new(vec![1.0, -0.5, 2.0, 0.25]);
+
+        let mut cholesky = Cholesky::decompose(a.clone());
+        cholesky.update(v.clone());
+
+        let v_mat = Matrix::new(4, 1, v.into_vec());
+        let expected = Cholesky::decompose(a + &v_mat * v_mat.transpose());
+        assert_close(cholesky.l(), expected.l(), 1e-12);
+    }
+
+    #[test]
+    fn test_cholesky_downdate() {
+        let a = test_matrix();
+        let v = Vector::new(vec![1.0, -0.5, 1.0, 0.25]);
+
+        let mut cholesky = Cholesky::decompose(a.clone());
+        cholesky.downdate(v.clone()).unwrap();
+
+        let v_mat = Matrix::new(4, 1, v.into_vec());
+        let expected = Cholesky::decompose(a - &v_mat * v_mat.transpose());
+        assert_close(cholesky.l(), expected.l(), 1e-12);
+    }
+
+    #[test]
+    fn test_cholesky_downdate_not_positive_definite() {
+        let a = test_matrix();
+        let mut cholesky = Cholesky::decompose(a.clone());
+        let l = cholesky.l().clone();
+
+        // A - vvᵀ has a negative diagonal entry.
+        assert!(cholesky.downdate(Vector::new(vec![3.0, 0.0, 0.0, 0.0])).is_err());
+        assert_eq!(*cholesky.l(), l);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cholesky_decompose_panics() {
+        let a = Matrix::new(2, 2, vec![-1.0, 0.0, 0.0, 1.0]);
+        let _ = Cholesky::decompose(a);
+    }
+}
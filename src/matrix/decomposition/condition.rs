@@ -0,0 +1,121 @@
+//! Condition numbers.
+//!
+//! The condition number bounds how much the solution of `Ax = b` can
+//! change relative to changes in `A` or `b`. Roughly, `log10(cond)`
+//! digits of accuracy are lost when solving the system.
+
+use std::any::Any;
+
+use matrix::Matrix;
+use error::Error;
+use epsilon::MachineEpsilon;
+
+use libnum::{Float, Signed};
+
+use super::PartialPivLu;
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Computes the condition number of the matrix in the 2-norm.
+    ///
+    /// This is the ratio of the largest and smallest singular values,
+    /// which is infinite for singular matrices. For non-square matrices
+    /// the smallest of the `min(m, n)` singular values is used.
+    ///
+    /// Only the singular values are computed, but this is still much more
+    /// expensive than `rcond_estimate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(2, 2, vec![3.0f64, 0.0, 0.0, -0.5]);
+    /// let cond = a.cond().unwrap();
+    ///
+    /// assert!((cond - 6.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The singular values could not be computed.
+    pub fn cond(&self) -> Result<T, Error> {
+        let s = try!(self.singular_values());
+
+        match (s.data().first(), s.data().last()) {
+            (Some(&s_max), Some(&s_min)) if s_min > T::zero() => Ok(s_max / s_min),
+            (Some(_), Some(_)) => Ok(T::infinity()),
+            _ => Ok(T::one()),
+        }
+    }
+
+    /// Estimates the reciprocal of the condition number of the matrix
+    /// in the 1-norm.
+    ///
+    /// The matrix is decomposed with `PartialPivLu` and the estimate is
+    /// computed by `PartialPivLu::rcond`. When the decomposition is needed
+    /// to solve systems anyway, it is cheaper to decompose the matrix once
+    /// and use the `rcond` method of the decomposition, or of `Cholesky`
+    /// for positive definite matrices.
+    ///
+    /// A value close to the machine epsilon means that the solution of
+    /// `Ax = b` may have no correct digits. Returns zero for singular
+    /// matrices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0f64, 1.0, 1.0, 1.0 + 1e-14]);
+    ///
+    /// // Solutions of a.solve(b) may have only two or three correct digits.
+    /// assert!(a.rcond_estimate() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    pub fn rcond_estimate(&self) -> T {
+        PartialPivLu::decompose(self.clone()).rcond()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+
+    /// The 1-norm of a matrix.
+    fn one_norm(a: &Matrix<f64>) -> f64 {
+        (0..a.cols()).fold(0.0, |acc, j| acc.max((0..a.rows()).map(|i| a[[i, j]].abs()).sum()))
+    }
+
+    #[test]
+    fn test_cond() {
+        let a = Matrix::new(3, 2, vec![2.0f64, 0.0, 0.0, 0.0, 0.0, 0.25]);
+        assert!((a.cond().unwrap() - 8.0).abs() < 1e-12);
+        assert!((a.transpose().cond().unwrap() - 8.0).abs() < 1e-12);
+
+        assert_eq!(Matrix::<f64>::identity(3).cond().unwrap(), 1.0);
+        assert_eq!(Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 4.0]).cond().unwrap(),
+                   ::std::f64::INFINITY);
+    }
+
+    #[test]
+    fn test_rcond_estimate() {
+        let a = Matrix::new(4, 4, vec![4.0, -1.0, 2.0, 0.5,
+                                       1.0, 3.0, -2.0, 1.0,
+                                       0.0, 2.0, 5.0, -1.0,
+                                       3.0, 1.0, 0.0, 2.0]);
+        let exact = 1.0 / (one_norm(&a) * one_norm(&a.clone().inverse().unwrap()));
+        let estimate = a.rcond_estimate();
+
+        assert!(estimate >= exact * (1.0 - 1e-12));
+        assert!(estimate <= 10.0 * exact);
+
+        // Hilbert matrices are notoriously ill-conditioned.
+        let hilbert = Matrix::from_fn(8, 8, |j, i| 1.0 / ((i + j + 1) as f64));
+        assert!(hilbert.rcond_estimate() < 1e-9);
+
+        assert_eq!(Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 4.0]).rcond_estimate(), 0.0);
+    }
+}
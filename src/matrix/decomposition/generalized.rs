@@ -0,0 +1,451 @@
+//! Generalized eigenvalue problems `A x = λ B x`.
+//!
+//! Symmetric-definite pencils are reduced to a standard symmetric eigenvalue
+//! problem using the Cholesky decomposition of `B`. General pencils are
+//! reduced to Hessenberg-triangular form and the eigenvalues are found with
+//! the (complex, single-shift) QZ iteration.
+//!
+//! References:
+//! 1. Matrix Computations, Golub and Van Loan, Sections 8.7 and 7.7.
+
+use std::any::Any;
+use std::cmp;
+use std::ops::Range;
+
+use matrix::{Matrix, BaseMatrix};
+use matrix::{forward_substitution_in_place, forward_substitution_transpose_in_place};
+use error::{Error, ErrorKind};
+use epsilon::MachineEpsilon;
+
+use libnum::{Float, Signed, cast};
+use num_complex::Complex;
+
+use super::{householder_vector, reflect_rows, rotate_rows, rotate_cols, givens_rot};
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Eigendecomposition of a symmetric-definite pencil.
+    ///
+    /// Solves the generalized eigenvalue problem `A x = λ B x` where `A` (self)
+    /// is symmetric and `B` is symmetric positive definite.
+    ///
+    /// Returns a Vec of eigenvalues sorted in ascending order, and a matrix with
+    /// the corresponding eigenvectors as the columns. The eigenvectors are
+    /// normalized so that `X^T B X = I`.
+    ///
+    /// The symmetry of the matrices is not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// // Stiffness and mass matrices of a two mass system
+    /// let k = Matrix::new(2, 2, vec![6.0f64, -2.0, -2.0, 4.0]);
+    /// let m = Matrix::new(2, 2, vec![2.0f64, 0.0, 0.0, 1.0]);
+    ///
+    /// let (e, _) = k.symmetric_generalized_eigendecomp(&m).unwrap();
+    ///
+    /// assert!((e[0] - 2.0).abs() < 1e-12);
+    /// assert!((e[1] - 5.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrices are not square.
+    /// - The matrices have different dimensions.
+    ///
+    /// # Failures
+    ///
+    /// - `B` is not positive definite.
+    /// - The symmetric QR iteration fails to converge.
+    pub fn symmetric_generalized_eigendecomp(&self, b: &Matrix<T>)
+                                             -> Result<(Vec<T>, Matrix<T>), Error> {
+        let n = self.rows();
+        assert!(n == self.cols, "Matrix must be square for generalized eigendecomp.");
+        assert!(b.rows() == n && b.cols() == n,
+                "Matrices must have the same dimensions for generalized eigendecomp.");
+
+        let l = try!(b.cholesky());
+
+        // C = L^-1 A L^-T, computed as L^-1 (L^-1 A)^T using the symmetry of A.
+        let mut w = self.clone();
+        forward_substitution_in_place(&l, &mut w, false);
+        let mut c = w.transpose();
+        forward_substitution_in_place(&l, &mut c, false);
+
+        let (eigenvalues, mut x) = try!(c.symmetric_eigendecomp());
+
+        // The eigenvectors of the pencil are X = L^-T Y.
+        forward_substitution_transpose_in_place(&l, &mut x, false);
+
+        Ok((eigenvalues, x))
+    }
+
+    /// Eigenvalues of a general pencil.
+    ///
+    /// Returns the (possibly complex) eigenvalues `λ` of the generalized eigenvalue
+    /// problem `A x = λ B x`, where `A` is self. The eigenvalues are computed using
+    /// the QZ algorithm and `B` may be singular.
+    ///
+    /// Infinite eigenvalues, which occur when `B` is singular, are reported as
+    /// `Complex::new(T::infinity(), T::zero())`. Due to rounding errors an infinite
+    /// eigenvalue may instead be reported as a very large finite value. If the pencil
+    /// is singular (`det(A - λB)` is zero for all `λ`) some eigenvalues will be NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0f64, 2.0, 0.0, 3.0]);
+    /// let b = Matrix::new(2, 2, vec![1.0f64, 0.0, 0.0, 0.0]);
+    ///
+    /// let e = a.generalized_eigenvalues(&b).unwrap();
+    ///
+    /// assert!(e.iter().any(|x| (x.re - 1.0).abs() < 1e-12 && x.im == 0.0));
+    /// assert!(e.iter().any(|x| x.re.is_infinite()));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrices are not square.
+    /// - The matrices have different dimensions.
+    ///
+    /// # Failures
+    ///
+    /// - The QZ iteration fails to converge.
+    pub fn generalized_eigenvalues(&self, b: &Matrix<T>) -> Result<Vec<Complex<T>>, Error> {
+        let n = self.rows();
+        assert!(n == self.cols, "Matrix must be square for generalized eigenvalues.");
+        assert!(b.rows() == n && b.cols() == n,
+                "Matrices must have the same dimensions for generalized eigenvalues.");
+
+        let mut h = self.clone();
+        let mut t = b.clone();
+        hessenberg_triangular_reduction(&mut h, &mut t);
+
+        let mut h = Matrix::new(n, n, h.data.iter().map(|&x| Complex::new(x, T::zero())).collect::<Vec<_>>());
+        let mut t = Matrix::new(n, n, t.data.iter().map(|&x| Complex::new(x, T::zero())).collect::<Vec<_>>());
+        qz_iteration(&mut h, &mut t)
+    }
+}
+
+/// Reduces the pencil `(a, b)` to Hessenberg-triangular form in place, with
+/// `a` upper Hessenberg and `b` upper triangular.
+fn hessenberg_triangular_reduction<T: Float>(a: &mut Matrix<T>, b: &mut Matrix<T>) {
+    let n = a.rows();
+
+    // Reduce b to upper triangular form with a QR decomposition.
+    for k in 0..n.saturating_sub(1) {
+        let column = (k..n).map(|i| b[[i, k]]).collect::<Vec<_>>();
+        let (v, tau, _) = householder_vector(&column);
+
+        reflect_rows(b, k, &v, tau, k..n);
+        reflect_rows(a, k, &v, tau, 0..n);
+
+        for i in k + 1..n {
+            b[[i, k]] = T::zero();
+        }
+    }
+
+    // Zero the entries of a below the subdiagonal, restoring the triangular
+    // form of b after each rotation.
+    for j in 0..n.saturating_sub(2) {
+        for i in (j + 2..n).rev() {
+            let (c, s) = givens_rot(a[[i - 1, j]], a[[i, j]]);
+            rotate_rows(a, i - 1, i, c, s, j..n);
+            rotate_rows(b, i - 1, i, c, s, i - 1..n);
+            a[[i, j]] = T::zero();
+
+            let (c, s) = givens_rot(b[[i, i]], b[[i, i - 1]]);
+            rotate_cols(b, i - 1, i, c, -s, 0..i + 1);
+            rotate_cols(a, i - 1, i, c, -s, 0..n);
+            b[[i, i - 1]] = T::zero();
+        }
+    }
+}
+
+/// Computes the eigenvalues of the Hessenberg-triangular pencil `(h, t)`
+/// using the single-shift complex QZ iteration.
+///
+/// Zeros on the diagonal of `t` are chased to the bottom of the active block
+/// and deflated as infinite eigenvalues.
+fn qz_iteration<T>(h: &mut Matrix<Complex<T>>, t: &mut Matrix<Complex<T>>) -> Result<Vec<Complex<T>>, Error>
+    where T: Any + Float + MachineEpsilon
+{
+    let n = h.rows();
+    let zero = Complex::new(T::zero(), T::zero());
+    let mut eigenvalues = vec![zero; n];
+
+    if n == 0 {
+        return Ok(eigenvalues);
+    }
+
+    let eps = <T as MachineEpsilon>::epsilon();
+    let frobenius = |m: &Matrix<Complex<T>>| {
+        m.data.iter().fold(T::zero(), |acc, x| acc + x.norm_sqr()).sqrt()
+    };
+    let h_norm = frobenius(h);
+    let t_norm = frobenius(t);
+
+    let max_iterations = 30 * cmp::max(n, 10);
+    let mut total_iterations = 0;
+    let mut iterations = 0;
+
+    // The final index of the active block
+    let mut hi = n - 1;
+
+    loop {
+        // Find the start of the active block by looking for a negligible subdiagonal
+        let mut lo = hi;
+        while lo > 0 {
+            let mut s = h[[lo - 1, lo - 1]].norm() + h[[lo, lo]].norm();
+            if s == T::zero() {
+                s = h_norm;
+            }
+
+            if h[[lo, lo - 1]].norm() <= eps * s {
+                h[[lo, lo - 1]] = zero;
+                break;
+            }
+            lo -= 1;
+        }
+
+        if lo == hi {
+            eigenvalues[hi] = pencil_eigenvalue(h[[hi, hi]], t[[hi, hi]], eps * t_norm);
+            if hi == 0 {
+                break;
+            }
+            hi -= 1;
+            iterations = 0;
+            continue;
+        }
+
+        // A negligible diagonal entry of t is an infinite eigenvalue, chase it to the
+        // bottom of the active block where it can be deflated.
+        if let Some(k) = (lo..hi + 1).rev().find(|&k| t[[k, k]].norm() <= eps * t_norm) {
+            t[[k, k]] = zero;
+
+            for j in k..hi {
+                let (c, s) = complex_rotation(t[[j, j + 1]], t[[j + 1, j + 1]]);
+                complex_rotate_rows(t, j, j + 1, c, s, j + 1..hi + 1);
+                complex_rotate_rows(h, j, j + 1, c, s, cmp::max(j, lo + 1) - 1..hi + 1);
+                t[[j + 1, j + 1]] = zero;
+
+                if j > lo {
+                    let (c, s) = complex_rotation(h[[j + 1, j]], h[[j + 1, j - 1]]);
+                    complex_rotate_cols(h, j - 1, j, c, s, lo..j + 2);
+                    complex_rotate_cols(t, j - 1, j, c, s, lo..j);
+                    h[[j + 1, j - 1]] = zero;
+                }
+            }
+
+            let (c, s) = complex_rotation(h[[hi, hi]], h[[hi, hi - 1]]);
+            complex_rotate_cols(h, hi - 1, hi, c, s, lo..hi + 1);
+            complex_rotate_cols(t, hi - 1, hi, c, s, lo..hi);
+            h[[hi, hi - 1]] = zero;
+            continue;
+        }
+
+        iterations += 1;
+        total_iterations += 1;
+        if total_iterations > max_iterations {
+            return Err(Error::new(ErrorKind::DecompFailure,
+                                  "QZ iteration did not converge."));
+        }
+
+        // The shift is the eigenvalue of the trailing 2x2 block of T^-1 H closest to
+        // the final diagonal entry. Use an exceptional shift if convergence is slow.
+        let (h11, h12, h21, h22) = (h[[hi - 1, hi - 1]], h[[hi - 1, hi]], h[[hi, hi - 1]], h[[hi, hi]]);
+        let (t11, t12, t22) = (t[[hi - 1, hi - 1]], t[[hi - 1, hi]], t[[hi, hi]]);
+
+        let m21 = h21 / t22;
+        let m22 = h22 / t22;
+        let shift = if iterations % 10 == 0 {
+            m22 + Complex::new(cast::<f64, T>(1.5).unwrap() * m21.norm(), T::zero())
+        } else {
+            let m11 = (h11 - t12 * m21) / t11;
+            let m12 = (h12 - t12 * m22) / t11;
+
+            let p = (m11 - m22).unscale(T::one() + T::one());
+            let d = (p * p + m12 * m21).sqrt();
+            let denom = if (p + d).norm() >= (p - d).norm() { p + d } else { p - d };
+
+            if denom == zero {
+                m22
+            } else {
+                m22 - m12 * m21 / denom
+            }
+        };
+
+        // Chase the bulge down the active block
+        let (c, s) = complex_rotation(h[[lo, lo]] - shift * t[[lo, lo]], h[[lo + 1, lo]]);
+        complex_rotate_rows(h, lo, lo + 1, c, s, lo..hi + 1);
+        complex_rotate_rows(t, lo, lo + 1, c, s, lo..hi + 1);
+
+        for k in lo..hi {
+            // Restore the triangular form of t
+            let (c, s) = complex_rotation(t[[k + 1, k + 1]], t[[k + 1, k]]);
+            complex_rotate_cols(t, k, k + 1, c, s, lo..k + 2);
+            complex_rotate_cols(h, k, k + 1, c, s, lo..cmp::min(k + 3, hi + 1));
+            t[[k + 1, k]] = zero;
+
+            // Restore the Hessenberg form of h
+            if k + 1 < hi {
+                let (c, s) = complex_rotation(h[[k + 1, k]], h[[k + 2, k]]);
+                complex_rotate_rows(h, k + 1, k + 2, c, s, k..hi + 1);
+                complex_rotate_rows(t, k + 1, k + 2, c, s, k + 1..hi + 1);
+                h[[k + 2, k]] = zero;
+            }
+        }
+    }
+
+    Ok(eigenvalues)
+}
+
+/// Returns the eigenvalue `h / t` of a 1x1 pencil, treating `t`
+/// smaller than `small` as zero.
+fn pencil_eigenvalue<T: Float>(h: Complex<T>, t: Complex<T>, small: T) -> Complex<T> {
+    if t.norm() > small {
+        h / t
+    } else if h.norm() > T::zero() {
+        Complex::new(T::infinity(), T::zero())
+    } else {
+        Complex::new(T::nan(), T::nan())
+    }
+}
+
+/// Computes the complex Givens rotation `[c s; -conj(s) c]`, with `c` real,
+/// which maps `[a; b]` to `[r; 0]`.
+fn complex_rotation<T: Float>(a: Complex<T>, b: Complex<T>) -> (T, Complex<T>) {
+    let a_norm = a.norm();
+    let r = a_norm.hypot(b.norm());
+
+    if r == T::zero() {
+        (T::one(), Complex::new(T::zero(), T::zero()))
+    } else if a_norm == T::zero() {
+        (T::zero(), Complex::new(T::one(), T::zero()))
+    } else {
+        (a_norm / r, a.unscale(a_norm) * b.conj().unscale(r))
+    }
+}
+
+/// Applies the complex rotation `[c s; -conj(s) c]` from the left to
+/// the rows `i` and `j` of `m`, restricted to the given columns.
+fn complex_rotate_rows<T: Float>(m: &mut Matrix<Complex<T>>,
+                                 i: usize,
+                                 j: usize,
+                                 c: T,
+                                 s: Complex<T>,
+                                 cols: Range<usize>) {
+    for k in cols {
+        let (x, y) = (m[[i, k]], m[[j, k]]);
+        m[[i, k]] = x.scale(c) + s * y;
+        m[[j, k]] = y.scale(c) - s.conj() * x;
+    }
+}
+
+/// Applies the unitary matrix `[c -conj(s); s c]` from the right to the columns
+/// `i` and `j` of `m`, restricted to the given rows.
+///
+/// With `(c, s)` the rotation for `(m[[k, j]], m[[k, i]])` this zeros `m[[k, i]]`.
+fn complex_rotate_cols<T: Float>(m: &mut Matrix<Complex<T>>,
+                                 i: usize,
+                                 j: usize,
+                                 c: T,
+                                 s: Complex<T>,
+                                 rows: Range<usize>) {
+    for k in rows {
+        let (x, y) = (m[[k, i]], m[[k, j]]);
+        m[[k, i]] = x.scale(c) - s.conj() * y;
+        m[[k, j]] = x * s + y.scale(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+
+    fn assert_eigenvalues(actual: &[(f64, f64)], expected: &[(f64, f64)], tol: f64) {
+        assert_eq!(actual.len(), expected.len());
+
+        for &(re, im) in expected {
+            assert!(actual.iter().any(|&(x, y)| (x - re).abs() < tol && (y - im).abs() < tol),
+                    format!("eigenvalue {} + {}i not found in {:?}", re, im, actual));
+        }
+    }
+
+    #[test]
+    fn test_symmetric_generalized_eigendecomp() {
+        let a = Matrix::new(3, 3, vec![2.0f64, -1.0, 0.0, -1.0, 2.0, -1.0, 0.0, -1.0, 2.0]);
+        let b = Matrix::new(3, 3, vec![4.0f64, 1.0, 0.0, 1.0, 4.0, 1.0, 0.0, 1.0, 4.0]);
+        let (eigenvalues, x) = a.symmetric_generalized_eigendecomp(&b).unwrap();
+
+        assert!(eigenvalues.windows(2).all(|w| w[0] <= w[1]));
+
+        // X^T B X = I
+        let xtbx = x.transpose() * &b * &x;
+        let identity = Matrix::<f64>::identity(3);
+        assert!(xtbx.data().iter().zip(identity.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        // A X = B X Λ
+        let ax = &a * &x;
+        let bxl = &b * &x * Matrix::from_diag(&eigenvalues);
+        assert!(ax.data().iter().zip(bxl.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_symmetric_generalized_eigendecomp_not_definite() {
+        let a = Matrix::new(2, 2, vec![1.0f64, 0.0, 0.0, 1.0]);
+        let b = Matrix::new(2, 2, vec![1.0f64, 2.0, 2.0, 1.0]);
+
+        assert!(a.symmetric_generalized_eigendecomp(&b).is_err());
+    }
+
+    #[test]
+    fn test_generalized_eigenvalues_identity() {
+        let a = Matrix::new(4,
+                            4,
+                            vec![3.0f64, 1.0, 2.0, -1.0, 0.0, -1.0, 1.0, 0.5, 0.0, 0.0, 1.0, -4.0,
+                                 0.0, 0.0, 1.0, 1.0]);
+        let b = Matrix::<f64>::identity(4);
+        let eigs = a.generalized_eigenvalues(&b)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.re, e.im))
+            .collect::<Vec<_>>();
+
+        assert_eigenvalues(&eigs, &[(3.0, 0.0), (-1.0, 0.0), (1.0, 2.0), (1.0, -2.0)], 1e-10);
+    }
+
+    #[test]
+    fn test_generalized_eigenvalues_general() {
+        // det(A - λB) = (1 - 2λ)(4 - λ) + 2 = 2λ² - 9λ + 6
+        let a = Matrix::new(2, 2, vec![1.0f64, 2.0, -1.0, 4.0]);
+        let b = Matrix::new(2, 2, vec![2.0f64, 0.0, 0.0, 1.0]);
+        let eigs = a.generalized_eigenvalues(&b)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.re, e.im))
+            .collect::<Vec<_>>();
+
+        let d = 33f64.sqrt();
+        assert_eigenvalues(&eigs, &[((9.0 + d) / 4.0, 0.0), ((9.0 - d) / 4.0, 0.0)], 1e-10);
+    }
+
+    #[test]
+    fn test_generalized_eigenvalues_infinite() {
+        let a = Matrix::new(3, 3, vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 10.0]);
+        let b = Matrix::new(3, 3, vec![1.0f64, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+        let eigs = a.generalized_eigenvalues(&b).unwrap();
+
+        assert_eq!(eigs.iter().filter(|e| e.re.is_infinite()).count(), 1);
+
+        // The finite eigenvalues satisfy det(A - λB) = 0, and
+        // det(A - λB) = 10λ² + 9λ - 3 here.
+        for e in eigs.iter().filter(|e| e.re.is_finite()) {
+            let p = e * e * 10.0 + e * 9.0 - 3.0;
+            assert!(p.norm() < 1e-10);
+        }
+    }
+}
@@ -0,0 +1,489 @@
+//! LDLᵀ decomposition of symmetric indefinite matrices.
+//!
+//! The matrix is factored with the symmetric pivoting strategy of Bunch and
+//! Kaufman, which uses both 1x1 and 2x2 pivots. This is stable for any
+//! symmetric matrix, and unlike the LU decomposition preserves the symmetry.
+//!
+//! References:
+//! 1. Matrix Computations, Golub and Van Loan, Section 4.4.
+//! 2. [Some stable methods for calculating inertia and solving symmetric linear systems]
+//!    (https://doi.org/10.1090/S0025-5718-1977-0428694-0), Bunch and Kaufman
+
+use std::any::Any;
+
+use matrix::{Matrix, BaseMatrix};
+use matrix::{forward_substitution_in_place, forward_substitution_transpose_in_place};
+use vector::Vector;
+use error::{Error, ErrorKind};
+
+use libnum::Float;
+
+/// The LDL<sup>T</sup> decomposition with Bunch-Kaufman pivoting of a
+/// symmetric matrix.
+///
+/// The decomposition is PAP<sup>T</sup> = LDL<sup>T</sup>, where P is a
+/// permutation matrix, L is unit lower triangular and D is block diagonal
+/// with 1x1 and 2x2 blocks.
+///
+/// # Examples
+///
+/// ```
+/// use rulinalg::matrix::Matrix;
+/// use rulinalg::matrix::decomposition::Ldlt;
+/// use rulinalg::vector::Vector;
+///
+/// // A saddle point system, which has no Cholesky decomposition.
+/// let a = Matrix::new(3, 3, vec![2.0f64, 0.0, 1.0,
+///                                0.0, 2.0, 1.0,
+///                                1.0, 1.0, 0.0]);
+///
+/// let ldlt = Ldlt::decompose(a);
+///
+/// // Two positive and one negative eigenvalue.
+/// assert_eq!(ldlt.inertia(), (2, 1, 0));
+/// assert!((ldlt.det() + 4.0).abs() < 1e-12);
+///
+/// let x = ldlt.solve(Vector::new(vec![3.0, 3.0, 2.0])).unwrap();
+/// assert!(x.data().iter().all(|x_i| (x_i - 1.0).abs() < 1e-12));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Ldlt<T> {
+    l: Matrix<T>,
+    // The diagonal of D, and its subdiagonal which is non-zero
+    // only for the 2x2 blocks.
+    d_diag: Vec<T>,
+    d_sub: Vec<T>,
+    // Row i of PAPᵀ is row perm[i] of A, with the columns permuted alike.
+    perm: Vec<usize>,
+    // The 1-norm of the decomposed matrix.
+    norm: T,
+}
+
+impl<T: Any + Float> Ldlt<T> {
+    /// Computes the LDL<sup>T</sup> decomposition of the matrix.
+    ///
+    /// Only the lower triangle of the matrix is used, the matrix is
+    /// assumed to be symmetric. Singular matrices can be decomposed, but
+    /// the resulting decomposition cannot be used to solve systems.
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    pub fn decompose(matrix: Matrix<T>) -> Ldlt<T> {
+        let n = matrix.rows();
+        assert!(n == matrix.cols(), "Matrix must be square for LDLT decomposition.");
+
+        let mut a = matrix;
+        let norm = (0..n).fold(T::zero(), |acc, i| {
+            acc.max((0..n).fold(T::zero(), |sum, j| sum + sym_entry(&a, i, j).abs()))
+        });
+
+        let alpha = (T::one() + T::from(17).unwrap().sqrt()) / T::from(8).unwrap();
+        let mut perm = (0..n).collect::<Vec<_>>();
+        let mut d_diag = vec![T::zero(); n];
+        let mut d_sub = vec![T::zero(); n.saturating_sub(1)];
+
+        let mut k = 0;
+        while k < n {
+            let abs_akk = a[[k, k]].abs();
+            let (mut i_max, mut col_max) = (k, T::zero());
+            for i in k + 1..n {
+                if a[[i, k]].abs() > col_max {
+                    i_max = i;
+                    col_max = a[[i, k]].abs();
+                }
+            }
+
+            let (pivot, step) = if abs_akk.max(col_max) == T::zero() || abs_akk >= alpha * col_max {
+                (k, 1)
+            } else {
+                // The largest off-diagonal entry in row i_max of the trailing matrix.
+                let row_max = (k..n)
+                    .filter(|&j| j != i_max)
+                    .map(|j| sym_entry(&a, i_max, j).abs())
+                    .fold(T::zero(), |acc, x| acc.max(x));
+
+                if abs_akk * row_max >= alpha * col_max * col_max {
+                    (k, 1)
+                } else if a[[i_max, i_max]].abs() >= alpha * row_max {
+                    (i_max, 1)
+                } else {
+                    (i_max, 2)
+                }
+            };
+
+            let kk = k + step - 1;
+            if pivot != kk {
+                symmetric_swap(&mut a, kk, pivot);
+                perm.swap(kk, pivot);
+            }
+
+            if step == 1 {
+                let d = a[[k, k]];
+                d_diag[k] = d;
+
+                if d != T::zero() {
+                    for i in k + 1..n {
+                        let w_i = a[[i, k]];
+                        let l_i = w_i / d;
+                        for j in k + 1..i + 1 {
+                            a[[i, j]] = a[[i, j]] - l_i * a[[j, k]];
+                        }
+                    }
+
+                    for i in k + 1..n {
+                        a[[i, k]] = a[[i, k]] / d;
+                    }
+                }
+            } else {
+                let (d11, d21, d22) = (a[[k, k]], a[[k + 1, k]], a[[k + 1, k + 1]]);
+                let det = d11 * d22 - d21 * d21;
+                d_diag[k] = d11;
+                d_diag[k + 1] = d22;
+                d_sub[k] = d21;
+
+                // Rows of [w_k w_k+1] D⁻¹, computed before the update
+                // overwrites the columns k and k + 1.
+                let l_rows = (k + 2..n)
+                    .map(|i| {
+                        let (w1, w2) = (a[[i, k]], a[[i, k + 1]]);
+                        ((w1 * d22 - w2 * d21) / det, (w2 * d11 - w1 * d21) / det)
+                    })
+                    .collect::<Vec<_>>();
+
+                for i in k + 2..n {
+                    let (l1, l2) = l_rows[i - k - 2];
+                    for j in k + 2..i + 1 {
+                        a[[i, j]] = a[[i, j]] - l1 * a[[j, k]] - l2 * a[[j, k + 1]];
+                    }
+                }
+
+                for i in k + 2..n {
+                    let (l1, l2) = l_rows[i - k - 2];
+                    a[[i, k]] = l1;
+                    a[[i, k + 1]] = l2;
+                }
+                a[[k + 1, k]] = T::zero();
+            }
+
+            k += step;
+        }
+
+        // Keep the strictly lower triangle as L, with a unit diagonal.
+        for i in 0..n {
+            a[[i, i]] = T::one();
+            for j in i + 1..n {
+                a[[i, j]] = T::zero();
+            }
+        }
+
+        Ldlt {
+            l: a,
+            d_diag: d_diag,
+            d_sub: d_sub,
+            perm: perm,
+            norm: norm,
+        }
+    }
+
+    /// The unit lower triangular factor L.
+    pub fn l(&self) -> &Matrix<T> {
+        &self.l
+    }
+
+    /// The block diagonal factor D.
+    pub fn d(&self) -> Matrix<T> {
+        let n = self.d_diag.len();
+        let mut d = Matrix::from_diag(&self.d_diag);
+        for i in 0..n.saturating_sub(1) {
+            d[[i + 1, i]] = self.d_sub[i];
+            d[[i, i + 1]] = self.d_sub[i];
+        }
+        d
+    }
+
+    /// The symmetric permutation, such that row `i` of PAP<sup>T</sup> is
+    /// row `permutation()[i]` of A with its columns reordered likewise.
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Computes the inertia of the decomposed matrix.
+    ///
+    /// Returns the number of positive, negative and zero eigenvalues.
+    /// By Sylvester's law of inertia these are the same as for D.
+    /// Eigenvalues of D which are negligible relative to the norm
+    /// of the matrix are counted as zero.
+    pub fn inertia(&self) -> (usize, usize, usize) {
+        let n = self.d_diag.len();
+        let tol = T::from(n).unwrap() * T::epsilon() * self.norm;
+        let (mut positive, mut negative, mut zero) = (0, 0, 0);
+
+        {
+            let mut count = |x: T| if x.abs() <= tol {
+                zero += 1;
+            } else if x > T::zero() {
+                positive += 1;
+            } else {
+                negative += 1;
+            };
+
+            let mut k = 0;
+            while k < n {
+                if self.is_block(k) {
+                    let (a, b, c) = (self.d_diag[k], self.d_sub[k], self.d_diag[k + 1]);
+                    let two = T::one() + T::one();
+                    let mean = (a + c) / two;
+                    let radius = ((a - c) / two).hypot(b);
+
+                    count(mean + radius);
+                    count(mean - radius);
+                    k += 2;
+                } else {
+                    count(self.d_diag[k]);
+                    k += 1;
+                }
+            }
+        }
+
+        (positive, negative, zero)
+    }
+
+    /// Computes the determinant of the decomposed matrix.
+    pub fn det(&self) -> T {
+        let n = self.d_diag.len();
+        let mut det = T::one();
+
+        let mut k = 0;
+        while k < n {
+            if self.is_block(k) {
+                det = det * (self.d_diag[k] * self.d_diag[k + 1] - self.d_sub[k] * self.d_sub[k]);
+                k += 2;
+            } else {
+                det = det * self.d_diag[k];
+                k += 1;
+            }
+        }
+
+        det
+    }
+
+    /// Solves the equation `Ax = b`.
+    ///
+    /// # Panics
+    ///
+    /// - The size of `b` does not match the size of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve(&self, b: Vector<T>) -> Result<Vector<T>, Error> {
+        let size = b.size();
+        let x = try!(self.solve_matrix(Matrix::new(size, 1, b.into_vec())));
+        Ok(Vector::new(x.into_vec()))
+    }
+
+    /// Solves the equation `AX = B` for all columns of `B` at once.
+    ///
+    /// # Panics
+    ///
+    /// - The number of rows of `b` does not match the size of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve_matrix(&self, b: Matrix<T>) -> Result<Matrix<T>, Error> {
+        let n = self.d_diag.len();
+        assert!(b.rows() == n,
+                "Right-hand side must have the same number of rows as the matrix.");
+
+        let cols = b.cols();
+        let mut y = b.select_rows(&self.perm);
+
+        // Solve L Z = PB.
+        forward_substitution_in_place(&self.l, &mut y, true);
+
+        // Solve D W = Z, block by block.
+        let mut k = 0;
+        while k < n {
+            if self.is_block(k) {
+                let (a, b, c) = (self.d_diag[k], self.d_sub[k], self.d_diag[k + 1]);
+                let det = a * c - b * b;
+                if det == T::zero() {
+                    return Err(Error::new(ErrorKind::AlgebraFailure,
+                                          "Linear system cannot be solved (matrix is singular)."));
+                }
+
+                for j in 0..cols {
+                    let (y1, y2) = (y[[k, j]], y[[k + 1, j]]);
+                    y[[k, j]] = (c * y1 - b * y2) / det;
+                    y[[k + 1, j]] = (a * y2 - b * y1) / det;
+                }
+                k += 2;
+            } else {
+                let d = self.d_diag[k];
+                if d == T::zero() {
+                    return Err(Error::new(ErrorKind::AlgebraFailure,
+                                          "Linear system cannot be solved (matrix is singular)."));
+                }
+
+                for j in 0..cols {
+                    y[[k, j]] = y[[k, j]] / d;
+                }
+                k += 1;
+            }
+        }
+
+        // Solve Lᵀ U = W.
+        forward_substitution_transpose_in_place(&self.l, &mut y, true);
+
+        // Undo the permutation, X = PᵀU.
+        let mut inverse_perm = vec![0; n];
+        for (i, &p) in self.perm.iter().enumerate() {
+            inverse_perm[p] = i;
+        }
+
+        Ok(y.select_rows(&inverse_perm))
+    }
+
+    /// Whether a 2x2 block of D starts at index `k`.
+    fn is_block(&self, k: usize) -> bool {
+        k + 1 < self.d_diag.len() && self.d_sub[k] != T::zero()
+    }
+}
+
+/// Returns the entry `(i, j)` of a symmetric matrix stored in the
+/// lower triangle of `a`.
+fn sym_entry<T: Copy>(a: &Matrix<T>, i: usize, j: usize) -> T {
+    if i >= j { a[[i, j]] } else { a[[j, i]] }
+}
+
+/// Symmetrically interchanges the rows and columns `p < q` of the
+/// matrix stored in the lower triangle of `a`.
+///
+/// The columns which already hold the computed part of L only have
+/// their rows `p` and `q` interchanged, as required.
+fn symmetric_swap<T: Copy>(a: &mut Matrix<T>, p: usize, q: usize) {
+    let n = a.rows();
+    let swap = |a: &mut Matrix<T>, x: [usize; 2], y: [usize; 2]| {
+        let tmp = a[x];
+        a[x] = a[y];
+        a[y] = tmp;
+    };
+
+    for j in 0..p {
+        swap(a, [p, j], [q, j]);
+    }
+
+    swap(a, [p, p], [q, q]);
+
+    for i in p + 1..q {
+        swap(a, [i, p], [q, i]);
+    }
+
+    for i in q + 1..n {
+        swap(a, [i, p], [i, q]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use vector::Vector;
+
+    use super::Ldlt;
+
+    fn validate_ldlt(a: &Matrix<f64>) -> Ldlt<f64> {
+        let ldlt = Ldlt::decompose(a.clone());
+        let n = a.rows();
+
+        let l = ldlt.l();
+        for i in 0..n {
+            assert_eq!(l[[i, i]], 1.0);
+            for j in i + 1..n {
+                assert_eq!(l[[i, j]], 0.0);
+            }
+        }
+
+        let p = ldlt.permutation();
+        let pap = a.select(p, p);
+        let ldl = l * ldlt.d() * l.transpose();
+        assert!(ldl.data().iter().zip(pap.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        ldlt
+    }
+
+    #[test]
+    fn test_ldlt_indefinite() {
+        // Forces 2x2 pivots: the diagonal is small relative to the rest.
+        let a = Matrix::new(4,
+                            4,
+                            vec![0.0, 1.0, 2.0, 3.0, 1.0, 0.0, 4.0, 1.0, 2.0, 4.0, 0.0, 5.0, 3.0,
+                                 1.0, 5.0, 0.001]);
+        let ldlt = validate_ldlt(&a);
+
+        assert!(ldlt.d_sub.iter().any(|&x| x != 0.0));
+        assert!((ldlt.det() - a.det()).abs() < 1e-10);
+
+        let b = Vector::new(vec![1.0, 2.0, 3.0, 4.0]);
+        let x = ldlt.solve(b.clone()).unwrap();
+        let residual = &a * x - b;
+        assert!(residual.data().iter().all(|r| r.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_ldlt_solve_matrix() {
+        let a = Matrix::new(4,
+                            4,
+                            vec![0.0f64, 1.0, 2.0, 3.0, 1.0, 0.0, 4.0, 1.0, 2.0, 4.0, 0.0, 5.0,
+                                 3.0, 1.0, 5.0, 0.001]);
+        let ldlt = Ldlt::decompose(a.clone());
+
+        let b = Matrix::new(4, 2, vec![1.0, 0.0, 2.0, -1.0, 3.0, 5.0, 4.0, 0.5]);
+        let x = ldlt.solve_matrix(b.clone()).unwrap();
+        let residual = &a * &x - &b;
+        assert!(residual.data().iter().all(|r| r.abs() < 1e-12));
+
+        // The first column of B solved on its own.
+        let x_0 = ldlt.solve(Vector::new(vec![1.0, 2.0, 3.0, 4.0])).unwrap();
+        assert!((0..4).all(|i| (x_0[i] - x[[i, 0]]).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_ldlt_inertia() {
+        let b = Matrix::new(5, 5, (0..25).map(|x| ((x * x) % 7) as f64).collect::<Vec<_>>());
+        let (_, q) = (&b + b.transpose()).symmetric_eigendecomp().unwrap();
+
+        // A symmetric matrix with eigenvalues 3, 1, 0, -2 and -5.
+        let a = &q * Matrix::from_diag(&[3.0, 1.0, 0.0, -2.0, -5.0]) * q.transpose();
+        let ldlt = validate_ldlt(&a);
+
+        assert_eq!(ldlt.inertia(), (2, 2, 1));
+        assert!(ldlt.det().abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_ldlt_positive_definite() {
+        let a = Matrix::new(3, 3, vec![4.0, 2.0, -2.0, 2.0, 10.0, 2.0, -2.0, 2.0, 5.0]);
+        let ldlt = validate_ldlt(&a);
+
+        assert_eq!(ldlt.inertia(), (3, 0, 0));
+        assert!((ldlt.det() - 108.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_ldlt_singular() {
+        let a = Matrix::new(3, 3, vec![1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0]);
+        let ldlt = validate_ldlt(&a);
+
+        assert_eq!(ldlt.inertia(), (1, 0, 2));
+        assert_eq!(ldlt.det(), 0.0);
+        assert!(ldlt.solve(Vector::new(vec![1.0, 1.0, 1.0])).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ldlt_non_square() {
+        let _ = Ldlt::decompose(Matrix::new(2, 3, vec![1.0; 6]));
+    }
+}
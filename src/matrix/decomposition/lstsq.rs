@@ -0,0 +1,243 @@
+//! Linear least squares.
+//!
+//! Solves `min ||Ax - b||` for matrices of any shape. When the solution is
+//! not unique, because A has more columns than rows or is rank deficient,
+//! the solution with the smallest norm is returned.
+
+use std::any::Any;
+use std::cmp;
+
+use matrix::{Matrix, BaseMatrix, forward_substitution};
+use vector::Vector;
+use error::{Error, ErrorKind};
+use epsilon::MachineEpsilon;
+use utils;
+
+use libnum::{Float, Signed};
+
+use super::{Cholesky, HouseholderQr, has_negligible_diagonal};
+
+/// The method used by `Matrix::lstsq` to solve least squares problems.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LstsqMethod<T> {
+    /// Solve the normal equations with a Cholesky decomposition.
+    ///
+    /// This is the fastest method, but squares the condition number
+    /// of the matrix and so loses accuracy on ill-conditioned problems.
+    /// The matrix must have full rank.
+    NormalEquations,
+    /// Use the Householder QR decomposition.
+    ///
+    /// This is accurate for well-conditioned problems and is a good default.
+    /// The matrix must have full rank.
+    Qr,
+    /// Use the singular value decomposition, treating singular values smaller
+    /// than the given cutoff times the largest singular value as zero.
+    ///
+    /// This is the slowest method, but handles rank deficient matrices.
+    Svd(T),
+}
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Solves the linear least squares problem `min ||Ax - b||`.
+    ///
+    /// Returns the tuple `(x, residual, rank)`, where `x` is the solution,
+    /// `residual` is the norm of `Ax - b` and `rank` is the numerical rank
+    /// of the matrix. When the matrix has more columns than rows, or with
+    /// `LstsqMethod::Svd` when it is rank deficient, `x` is the solution
+    /// with the smallest norm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::LstsqMethod;
+    /// use rulinalg::vector::Vector;
+    ///
+    /// // Fit a line through the points (0, 1), (1, 3), (2, 4) and (3, 7).
+    /// let a = Matrix::new(4, 2, vec![1.0f64, 0.0,
+    ///                                1.0, 1.0,
+    ///                                1.0, 2.0,
+    ///                                1.0, 3.0]);
+    /// let b = Vector::new(vec![1.0, 3.0, 4.0, 7.0]);
+    ///
+    /// let (x, residual, rank) = a.lstsq(b, LstsqMethod::Qr).unwrap();
+    ///
+    /// assert!((x[0] - 0.9).abs() < 1e-12);
+    /// assert!((x[1] - 1.9).abs() < 1e-12);
+    /// assert!((residual - 0.7f64.sqrt()).abs() < 1e-12);
+    /// assert_eq!(rank, 2);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The size of `b` does not match the number of rows.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix does not have full rank, with the normal equations or QR methods.
+    /// - The SVD could not be computed, with the SVD method.
+    pub fn lstsq(&self, b: Vector<T>, method: LstsqMethod<T>) -> Result<(Vector<T>, T, usize), Error> {
+        let (m, n) = (self.rows, self.cols);
+        assert!(b.size() == m,
+                "Right-hand side must have the same size as the number of rows.");
+
+        let (x, rank) = match method {
+            LstsqMethod::NormalEquations => (try!(self.lstsq_normal_equations(&b)), cmp::min(m, n)),
+            LstsqMethod::Qr => (try!(self.lstsq_qr(&b)), cmp::min(m, n)),
+            LstsqMethod::Svd(cutoff) => try!(self.lstsq_svd(&b, cutoff)),
+        };
+
+        let r = self * &x - b;
+        let residual = utils::dot(r.data(), r.data()).sqrt();
+
+        Ok((x, residual, rank))
+    }
+
+    fn lstsq_normal_equations(&self, b: &Vector<T>) -> Result<Vector<T>, Error> {
+        let rank_error = |_| {
+            Error::new(ErrorKind::AlgebraFailure,
+                       "Matrix does not have full rank, the normal equations cannot be solved.")
+        };
+
+        if self.rows >= self.cols {
+            // AᵀA x = Aᵀb
+            let at = self.transpose();
+            let cholesky = try!(Cholesky::try_decompose(&at * self).map_err(rank_error));
+            cholesky.solve(at * b)
+        } else {
+            // x = Aᵀy, where AAᵀ y = b
+            let at = self.transpose();
+            let cholesky = try!(Cholesky::try_decompose(self * &at).map_err(rank_error));
+            let y = try!(cholesky.solve(b.clone()));
+            Ok(at * y)
+        }
+    }
+
+    fn lstsq_qr(&self, b: &Vector<T>) -> Result<Vector<T>, Error> {
+        if self.rows >= self.cols {
+            return HouseholderQr::decompose(self.clone()).solve_least_squares(b.clone());
+        }
+
+        // With Aᵀ = QR the minimum norm solution is x = Q R⁻ᵀ b.
+        let (m, n) = (self.rows, self.cols);
+        let qr = HouseholderQr::decompose(self.transpose());
+        let r = qr.r();
+
+        if has_negligible_diagonal(&r, m, n) {
+            return Err(Error::new(ErrorKind::AlgebraFailure,
+                                  "Matrix does not have full row rank."));
+        }
+
+        let mut y = try!(forward_substitution(&r.transpose(), b.clone())).into_vec();
+        y.resize(n, T::zero());
+
+        Ok(qr.apply_q_vec(Vector::new(y)))
+    }
+
+    fn lstsq_svd(&self, b: &Vector<T>, cutoff: T) -> Result<(Vector<T>, usize), Error> {
+        let (s, u, v) = try!(self.clone().svd());
+        let k = s.rows();
+
+        let threshold = if k > 0 { cutoff * s[[0, 0]] } else { T::zero() };
+        let rank = (0..k).take_while(|&i| s[[i, i]] > threshold).count();
+
+        // x = V Σ⁺ Uᵀ b, using only the singular values above the threshold.
+        let c = u.transpose() * b;
+        let mut x = vec![T::zero(); self.cols];
+        for i in 0..rank {
+            let w = c[i] / s[[i, i]];
+            for (j, x_j) in x.iter_mut().enumerate() {
+                *x_j = *x_j + v[[j, i]] * w;
+            }
+        }
+
+        Ok((Vector::new(x), rank))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use vector::Vector;
+
+    use super::LstsqMethod;
+
+    fn methods() -> Vec<LstsqMethod<f64>> {
+        vec![LstsqMethod::NormalEquations, LstsqMethod::Qr, LstsqMethod::Svd(1e-12)]
+    }
+
+    #[test]
+    fn test_lstsq_overdetermined() {
+        let a = Matrix::new(5, 3, (0..15).map(|x| ((x * x) % 11) as f64).collect::<Vec<_>>());
+        let b = Vector::new(vec![1.0, -2.0, 0.5, 3.0, 4.0]);
+
+        for method in methods() {
+            let (x, residual, rank) = a.lstsq(b.clone(), method).unwrap();
+            assert_eq!(rank, 3);
+
+            // The residual is orthogonal to the columns of A.
+            let r = &a * &x - &b;
+            let normal = a.transpose() * &r;
+            assert!(normal.data().iter().all(|x| x.abs() < 1e-10));
+            assert!((residual - r.data().iter().map(|x| x * x).sum::<f64>().sqrt()).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_lstsq_underdetermined_minimum_norm() {
+        let a = Matrix::new(2, 4, vec![1.0, 2.0, 0.0, 1.0, 0.0, 1.0, 1.0, -1.0]);
+        let b = Vector::new(vec![3.0, 1.0]);
+
+        // The minimum norm solution lies in the row space of A, x = Aᵀ(AAᵀ)⁻¹b.
+        let aat = &a * a.transpose();
+        let expected = a.transpose() * aat.solve(b.clone()).unwrap();
+
+        for method in methods() {
+            let (x, residual, rank) = a.lstsq(b.clone(), method).unwrap();
+            assert_eq!(rank, 2);
+            assert!(residual < 1e-12);
+            assert!(x.data().iter().zip(expected.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+        }
+    }
+
+    #[test]
+    fn test_lstsq_rank_deficient() {
+        // The third column is the sum of the first two.
+        let a = Matrix::new(4, 3, vec![1.0f64, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 2.0, 2.0, -1.0, 1.0]);
+        let b = Vector::new(vec![1.0, 2.0, 3.0, 4.0]);
+
+        assert!(a.lstsq(b.clone(), LstsqMethod::NormalEquations).is_err());
+        assert!(a.lstsq(b.clone(), LstsqMethod::Qr).is_err());
+
+        let (x, _, rank) = a.lstsq(b.clone(), LstsqMethod::Svd(1e-12)).unwrap();
+        assert_eq!(rank, 2);
+
+        // The minimum norm solution is orthogonal to the null space (1, 1, -1).
+        assert!((x[0] + x[1] - x[2]).abs() < 1e-12);
+        let normal = a.transpose() * (&a * &x - b);
+        assert!(normal.data().iter().all(|x| x.abs() < 1e-10));
+
+        // The same check applies to the QR of the transpose for wide matrices.
+        let at = a.transpose();
+        assert!(at.lstsq(Vector::new(vec![1.0, 2.0, 3.0]), LstsqMethod::Qr).is_err());
+    }
+
+    #[test]
+    fn test_lstsq_zero_matrix() {
+        let a = Matrix::<f64>::zeros(3, 2);
+        let b = Vector::new(vec![1.0, 2.0, 2.0]);
+
+        let (x, residual, rank) = a.lstsq(b, LstsqMethod::Svd(1e-12)).unwrap();
+        assert_eq!(*x.data(), vec![0.0, 0.0]);
+        assert_eq!(residual, 3.0);
+        assert_eq!(rank, 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lstsq_wrong_size() {
+        let a = Matrix::new(3, 2, vec![1.0; 6]);
+        let _ = a.lstsq(Vector::new(vec![1.0, 2.0]), LstsqMethod::Qr);
+    }
+}
@@ -0,0 +1,648 @@
+//! LU decomposition with partial pivoting.
+//!
+//! The factors L and U are stored packed in a single matrix, with the
+//! unit diagonal of L implicit, and the row interchanges are stored as
+//! a permutation vector. Once computed, the decomposition can be reused
+//! to solve any number of systems with the same matrix.
+//!
+//! References:
+//! 1. Matrix Computations, Golub and Van Loan, Section 3.4.
+//! 2. [FORTRAN codes for estimating the one-norm of a real or complex matrix]
+//!    (https://doi.org/10.1145/50063.214386), Higham
+
+use std::any::Any;
+use std::f64;
+
+use matrix::{Matrix, BaseMatrix, BaseMatrixMut};
+use matrix::{back_substitution_in_place, forward_substitution_in_place};
+use matrix::{back_substitution_transpose_in_place, forward_substitution_transpose_in_place};
+use vector::Vector;
+use error::{Error, ErrorKind};
+use utils;
+
+use libnum::{Float, cast};
+
+use super::{inverse_norm_estimate, lu_in_place};
+
+/// The LU decomposition with partial pivoting of a square matrix.
+///
+/// The decomposition is PA = LU, where P is a permutation matrix, L is
+/// unit lower triangular and U is upper triangular.
+///
+/// # Examples
+///
+/// ```
+/// use rulinalg::matrix::Matrix;
+/// use rulinalg::matrix::decomposition::PartialPivLu;
+/// use rulinalg::vector::Vector;
+///
+/// let a = Matrix::new(3, 3, vec![1.0f64, 2.0, 0.0,
+///                                0.0, 3.0, 4.0,
+///                                5.0, 1.0, 2.0]);
+///
+/// let lu = PartialPivLu::decompose(a);
+///
+/// // The decomposition is computed once and reused for every system.
+/// let x = lu.solve(Vector::new(vec![3.0, 7.0, 8.0])).unwrap();
+/// let y = lu.solve(Vector::new(vec![1.0, 0.0, 5.0])).unwrap();
+///
+/// assert!(x.data().iter().all(|x_i| (x_i - 1.0).abs() < 1e-12));
+/// assert!((y[0] - 1.0).abs() < 1e-12);
+/// assert!((lu.det() - 42.0).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PartialPivLu<T> {
+    // U in the upper triangle, L strictly below the diagonal.
+    lu: Matrix<T>,
+    // Row i of PA is row perm[i] of A.
+    perm: Vec<usize>,
+    odd_perm: bool,
+    // The 1-norm of the decomposed matrix, used by `rcond`.
+    norm: T,
+}
+
+impl<T: Any + Float> PartialPivLu<T> {
+    /// Computes the LU decomposition of the matrix.
+    ///
+    /// The matrix is consumed and its storage is reused for the decomposition.
+    /// Singular matrices can be decomposed, but the resulting decomposition
+    /// cannot be used to solve systems or compute the inverse.
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    pub fn decompose(matrix: Matrix<T>) -> PartialPivLu<T> {
+        let n = matrix.rows();
+        assert!(n == matrix.cols(), "Matrix must be square for LU decomposition.");
+
+        let norm = (0..n).fold(T::zero(), |acc, j| {
+            acc.max((0..n).fold(T::zero(), |sum, i| sum + matrix[[i, j]].abs()))
+        });
+
+        let mut lu = matrix;
+        let (perm, odd_perm) = lu_in_place(&mut lu.as_mut_slice());
+
+        PartialPivLu {
+            lu: lu,
+            perm: perm,
+            odd_perm: odd_perm,
+            norm: norm,
+        }
+    }
+
+    /// The unit lower triangular factor L.
+    pub fn l(&self) -> Matrix<T> {
+        let n = self.lu.rows();
+        Matrix::from_fn(n, n, |j, i| if i == j {
+            T::one()
+        } else if j < i {
+            self.lu[[i, j]]
+        } else {
+            T::zero()
+        })
+    }
+
+    /// The upper triangular factor U.
+    pub fn u(&self) -> Matrix<T> {
+        let n = self.lu.rows();
+        Matrix::from_fn(n, n, |j, i| if j >= i { self.lu[[i, j]] } else { T::zero() })
+    }
+
+    /// The row permutation, such that row `i` of PA is row
+    /// `permutation()[i]` of A.
+    pub fn permutation(&self) -> &[usize] {
+        &self.perm
+    }
+
+    /// Solves the equation `Ax = b`.
+    ///
+    /// # Panics
+    ///
+    /// - The size of `b` does not match the size of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve(&self, b: Vector<T>) -> Result<Vector<T>, Error> {
+        let size = b.size();
+        let x = try!(self.solve_matrix(Matrix::new(size, 1, b.into_vec())));
+        Ok(Vector::new(x.into_vec()))
+    }
+
+    /// Solves the equation `AX = B` for all columns of `B` at once.
+    ///
+    /// # Panics
+    ///
+    /// - The number of rows of `b` does not match the size of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve_matrix(&self, b: Matrix<T>) -> Result<Matrix<T>, Error> {
+        assert!(b.rows() == self.lu.rows(),
+                "Right-hand side must have the same number of rows as the matrix.");
+
+        if self.is_singular() {
+            return Err(Error::new(ErrorKind::AlgebraFailure,
+                                  "Linear system cannot be solved (matrix is singular)."));
+        }
+
+        Ok(self.solve_unchecked(b))
+    }
+
+    /// Solves the equation `Aᵀx = b`.
+    ///
+    /// The existing factors are reused, so the transpose of the matrix
+    /// is never formed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::PartialPivLu;
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0f64, 2.0,
+    ///                                3.0, 4.0]);
+    /// let lu = PartialPivLu::decompose(a);
+    ///
+    /// let x = lu.solve_transpose(Vector::new(vec![4.0, 6.0])).unwrap();
+    /// assert!((x[0] - 1.0).abs() < 1e-12 && (x[1] - 1.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The size of `b` does not match the size of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve_transpose(&self, b: Vector<T>) -> Result<Vector<T>, Error> {
+        let size = b.size();
+        let x = try!(self.solve_transpose_matrix(Matrix::new(size, 1, b.into_vec())));
+        Ok(Vector::new(x.into_vec()))
+    }
+
+    /// Solves the equation `AᵀX = B` for all columns of `B` at once.
+    ///
+    /// # Panics
+    ///
+    /// - The number of rows of `b` does not match the size of the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve_transpose_matrix(&self, b: Matrix<T>) -> Result<Matrix<T>, Error> {
+        assert!(b.rows() == self.lu.rows(),
+                "Right-hand side must have the same number of rows as the matrix.");
+
+        if self.is_singular() {
+            return Err(Error::new(ErrorKind::AlgebraFailure,
+                                  "Linear system cannot be solved (matrix is singular)."));
+        }
+
+        Ok(self.solve_transpose_unchecked(b))
+    }
+
+    /// Solves the equation `Ax = b` with iterative refinement.
+    ///
+    /// The matrix `a` must be the matrix which was decomposed. After the
+    /// initial solve the residual `b - Ax` is computed in double precision
+    /// and used to correct the solution. This is repeated until the
+    /// corrections become negligible or stop decreasing, or `max_iters`
+    /// corrections have been made. For `f32` this is mixed precision
+    /// refinement, which usually gives a solution accurate to working
+    /// precision unless the matrix is very ill-conditioned.
+    ///
+    /// Returns the tuple `(x, forward_error, backward_error)`. The forward
+    /// error is an estimated bound on `||x - x_true|| / ||x||` in the
+    /// infinity norm. The backward error is the smallest relative change
+    /// to any entry of `A` or `b` for which `x` is the exact solution.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::PartialPivLu;
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let a = Matrix::new(3, 3, vec![1.0f32, 2.0, 0.0,
+    ///                                0.0, 3.0, 4.0,
+    ///                                5.0, 1.0, 2.0]);
+    /// let lu = PartialPivLu::decompose(a.clone());
+    ///
+    /// let (x, forward_error, backward_error) =
+    ///     lu.solve_refined(&a, Vector::new(vec![3.0, 7.0, 8.0]), 5).unwrap();
+    ///
+    /// assert!(x.data().iter().all(|x_i| (x_i - 1.0).abs() < 1e-6));
+    /// assert!(forward_error < 1e-5);
+    /// assert!(backward_error < 1e-6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The size of `a` or `b` does not match the size of the decomposition.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve_refined(&self,
+                         a: &Matrix<T>,
+                         b: Vector<T>,
+                         max_iters: usize)
+                         -> Result<(Vector<T>, T, T), Error> {
+        let n = self.lu.rows();
+        assert!(a.rows() == n && a.cols() == n,
+                "Matrix must have the same size as the decomposition.");
+
+        let mut x = try!(self.solve(b.clone())).into_vec();
+        let eps = T::epsilon();
+        let two = T::one() + T::one();
+        let inf_norm = |v: &[T]| v.iter().fold(T::zero(), |acc, v_i| acc.max(v_i.abs()));
+
+        let (mut r, mut scale) = refinement_residual(a, &b, &x);
+        let mut last_correction = T::infinity();
+        let mut iters = 0;
+
+        while iters < max_iters && r.iter().any(|r_i| *r_i != T::zero()) {
+            let dx = self.solve_unchecked(Matrix::new(n, 1, r)).into_vec();
+            utils::in_place_vec_bin_op(&mut x, &dx, |x_i, &dx_i| *x_i = *x_i + dx_i);
+
+            let (new_r, new_scale) = refinement_residual(a, &b, &x);
+            r = new_r;
+            scale = new_scale;
+            iters += 1;
+
+            // Stop when the correction is negligible or no longer shrinking quickly.
+            let correction = inf_norm(&dx);
+            if correction <= eps * inf_norm(&x) || two * correction > last_correction {
+                break;
+            }
+            last_correction = correction;
+        }
+
+        let backward_error = componentwise_backward_error(&r, &scale);
+
+        // The error is bounded by || |A⁻¹| w ||, where w = |r| + (n + 1) eps (|A||x| + |b|)
+        // accounts for the rounding errors in the double precision residual. This is
+        // equal to || diag(w) A⁻ᵀ ||₁, which is estimated from a few solves.
+        let n_eps = T::from((n + 1) as f64 * f64::EPSILON).unwrap();
+        let w = r.iter()
+            .zip(scale.iter())
+            .map(|(r_i, s_i)| r_i.abs() + n_eps * *s_i)
+            .collect::<Vec<_>>();
+
+        let scaled_solve = |v| {
+            let mut y = self.solve_transpose_unchecked(Matrix::new(n, 1, v)).into_vec();
+            utils::in_place_vec_bin_op(&mut y, &w, |y_i, &w_i| *y_i = *y_i * w_i);
+            y
+        };
+        let scaled_solve_transpose = |mut v: Vec<T>| {
+            utils::in_place_vec_bin_op(&mut v, &w, |v_i, &w_i| *v_i = *v_i * w_i);
+            self.solve_unchecked(Matrix::new(n, 1, v)).into_vec()
+        };
+        let error_norm = inverse_norm_estimate(n, scaled_solve, scaled_solve_transpose);
+
+        let x_norm = inf_norm(&x);
+        let forward_error = if x_norm > T::zero() {
+            error_norm / x_norm
+        } else {
+            T::zero()
+        };
+
+        Ok((Vector::new(x), forward_error, backward_error))
+    }
+
+    /// Computes the determinant of the decomposed matrix.
+    pub fn det(&self) -> T {
+        let d = (0..self.lu.rows()).fold(T::one(), |acc, i| acc * self.lu[[i, i]]);
+        if self.odd_perm { -d } else { d }
+    }
+
+    /// Computes the inverse of the decomposed matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn inverse(&self) -> Result<Matrix<T>, Error> {
+        let n = self.lu.rows();
+        self.solve_matrix(Matrix::identity(n)).map_err(|_| {
+            Error::new(ErrorKind::AlgebraFailure,
+                       "Matrix is singular and cannot be inverted.")
+        })
+    }
+
+    /// Estimates the reciprocal of the condition number of the matrix
+    /// in the 1-norm.
+    ///
+    /// The norm of the inverse is estimated with Higham's variant of
+    /// Hager's method, which only requires a few solves with the
+    /// existing decomposition. The estimate is never smaller than the
+    /// true reciprocal condition number, and is usually within a small
+    /// factor of it.
+    ///
+    /// Returns zero for singular matrices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::PartialPivLu;
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0f64, 1.0, 1.0, 1.0 + 1e-10]);
+    /// let rcond = PartialPivLu::decompose(a).rcond();
+    ///
+    /// assert!(rcond < 1e-10);
+    /// ```
+    pub fn rcond(&self) -> T {
+        let n = self.lu.rows();
+        if n == 0 {
+            return T::one();
+        }
+
+        if self.is_singular() || self.norm == T::zero() {
+            return T::zero();
+        }
+
+        let solve = |x| self.solve_unchecked(Matrix::new(n, 1, x)).into_vec();
+        let solve_transpose = |x| self.solve_transpose_unchecked(Matrix::new(n, 1, x)).into_vec();
+        let inverse_norm = inverse_norm_estimate(n, solve, solve_transpose);
+        T::one() / (self.norm * inverse_norm)
+    }
+
+    fn is_singular(&self) -> bool {
+        (0..self.lu.rows()).any(|i| self.lu[[i, i]] == T::zero())
+    }
+
+    /// Solves `AX = B`, assuming that U has a non-zero diagonal.
+    fn solve_unchecked(&self, b: Matrix<T>) -> Matrix<T> {
+        let mut x = b.select_rows(&self.perm);
+
+        forward_substitution_in_place(&self.lu, &mut x, true);
+        back_substitution_in_place(&self.lu, &mut x);
+
+        x
+    }
+
+    /// Solves `AᵀX = B`, assuming that U has a non-zero diagonal.
+    fn solve_transpose_unchecked(&self, b: Matrix<T>) -> Matrix<T> {
+        let mut y = b;
+
+        // Aᵀ = Uᵀ Lᵀ P, so first solve with the lower triangular Uᵀ,
+        // then with the unit upper triangular Lᵀ.
+        back_substitution_transpose_in_place(&self.lu, &mut y);
+        forward_substitution_transpose_in_place(&self.lu, &mut y, true);
+
+        // Finally undo the row permutation, x = Pᵀy.
+        let mut inverse_perm = vec![0; self.perm.len()];
+        for (i, &p) in self.perm.iter().enumerate() {
+            inverse_perm[p] = i;
+        }
+        y.select_rows(&inverse_perm)
+    }
+}
+
+impl<T: Any + Float> Matrix<T> {
+    /// Solves the equation `Ax = b` with iterative refinement.
+    ///
+    /// The matrix is decomposed with `PartialPivLu` and the solution is
+    /// refined as in `PartialPivLu::solve_refined`, which also describes
+    /// the returned tuple `(x, forward_error, backward_error)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let a = Matrix::new(2, 2, vec![2.0f32, 3.0, 1.0, 2.0]);
+    /// let (x, _, backward_error) = a.solve_refined(Vector::new(vec![13.0, 8.0]), 3).unwrap();
+    ///
+    /// assert!((x[0] - 2.0).abs() < 1e-6);
+    /// assert!((x[1] - 3.0).abs() < 1e-6);
+    /// assert!(backward_error < 1e-6);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    /// - The size of `b` does not match the number of rows.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve_refined(&self, b: Vector<T>, max_iters: usize) -> Result<(Vector<T>, T, T), Error> {
+        PartialPivLu::decompose(self.clone()).solve_refined(self, b, max_iters)
+    }
+}
+
+/// Computes the residual `b - Ax`, accumulated in double precision, and
+/// the componentwise scale `|A||x| + |b|`.
+fn refinement_residual<T: Float>(a: &Matrix<T>, b: &Vector<T>, x: &[T]) -> (Vec<T>, Vec<T>) {
+    let n = x.len();
+    let mut r = Vec::with_capacity(n);
+    let mut scale = Vec::with_capacity(n);
+
+    for (i, row) in a.iter_rows().enumerate() {
+        let mut r_i = cast::<T, f64>(b[i]).unwrap();
+        let mut scale_i = b[i].abs();
+        for (&a_ij, &x_j) in row.iter().zip(x.iter()) {
+            r_i -= cast::<T, f64>(a_ij).unwrap() * cast::<T, f64>(x_j).unwrap();
+            scale_i = scale_i + (a_ij * x_j).abs();
+        }
+        r.push(T::from(r_i).unwrap());
+        scale.push(scale_i);
+    }
+
+    (r, scale)
+}
+
+/// The componentwise backward error `max |r_i| / (|A||x| + |b|)_i`.
+fn componentwise_backward_error<T: Float>(r: &[T], scale: &[T]) -> T {
+    r.iter().zip(scale.iter()).fold(T::zero(), |acc, (r_i, s_i)| {
+        if *s_i > T::zero() { acc.max(r_i.abs() / *s_i) } else { acc }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use matrix::decomposition::tests::assert_close;
+    use vector::Vector;
+
+    use super::PartialPivLu;
+
+    fn test_matrix() -> Matrix<f64> {
+        Matrix::new(4,
+                    4,
+                    vec![2.0, -1.0, 0.0, 3.0, 4.0, 1.0, -2.0, 0.5, -1.0, 3.0, 5.0, 1.0, 0.0, 2.0,
+                         -1.0, 6.0])
+    }
+
+    #[test]
+    fn test_partial_piv_lu_factors() {
+        let a = test_matrix();
+        let lu = PartialPivLu::decompose(a.clone());
+
+        let (l, u) = (lu.l(), lu.u());
+        for i in 0..4 {
+            assert_eq!(l[[i, i]], 1.0);
+            for j in 0..i {
+                assert_eq!(u[[i, j]], 0.0);
+                assert_eq!(l[[j, i]], 0.0);
+                // Partial pivoting bounds the entries of L.
+                assert!(l[[i, j]].abs() <= 1.0);
+            }
+        }
+
+        assert_close(&(l * u), &a.select_rows(lu.permutation()), 1e-12);
+    }
+
+    #[test]
+    fn test_partial_piv_lu_solve() {
+        let a = test_matrix();
+        let lu = PartialPivLu::decompose(a.clone());
+
+        let b = Vector::new(vec![1.0, 2.0, 3.0, 4.0]);
+        let x = lu.solve(b.clone()).unwrap();
+        let residual = &a * x - b;
+        assert!(residual.data().iter().all(|r| r.abs() < 1e-12));
+
+        let b = Matrix::new(4, 2, vec![1.0, 0.0, 2.0, -1.0, 3.0, 5.0, 4.0, 0.5]);
+        let x = lu.solve_matrix(b.clone()).unwrap();
+        assert_close(&(&a * x), &b, 1e-12);
+    }
+
+    #[test]
+    fn test_partial_piv_lu_solve_transpose() {
+        let a = test_matrix();
+        let lu = PartialPivLu::decompose(a.clone());
+
+        let b = Vector::new(vec![1.0, 2.0, 3.0, 4.0]);
+        let x = lu.solve_transpose(b.clone()).unwrap();
+        let residual = a.transpose() * x - b;
+        assert!(residual.data().iter().all(|r| r.abs() < 1e-12));
+
+        // Large enough for the blocked triangular solves.
+        let n = 150;
+        let mut state = 2468u32;
+        let a = Matrix::from_fn(n, n, |_, _| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as f64 / 65536.0 - 0.5
+        });
+        let lu = PartialPivLu::decompose(a.clone());
+
+        let b = Matrix::from_fn(n, 3, |j, i| ((i * 3 + j * 7) % 11) as f64 - 5.0);
+        let x = lu.solve_transpose_matrix(b.clone()).unwrap();
+        assert_close(&(a.transpose() * x), &b, 1e-9);
+
+        let singular = PartialPivLu::decompose(Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 4.0]));
+        assert!(singular.solve_transpose(Vector::new(vec![1.0, 1.0])).is_err());
+    }
+
+    #[test]
+    fn test_partial_piv_lu_det_and_inverse() {
+        let a = test_matrix();
+        let lu = PartialPivLu::decompose(a.clone());
+
+        assert!((lu.det() - a.det()).abs() < 1e-10);
+
+        let inv = lu.inverse().unwrap();
+        assert_close(&(&a * inv), &Matrix::identity(4), 1e-12);
+
+        // A single row interchange flips the sign of the determinant.
+        let swapped = Matrix::new(2, 2, vec![0.0, 1.0, 1.0, 0.0]);
+        assert_eq!(PartialPivLu::decompose(swapped).det(), -1.0);
+    }
+
+    #[test]
+    fn test_partial_piv_lu_singular() {
+        let a = Matrix::new(3, 3, vec![1.0, 2.0, 3.0, 2.0, 4.0, 6.0, 1.0, 0.0, 1.0]);
+        let lu = PartialPivLu::decompose(a);
+
+        assert_eq!(lu.det(), 0.0);
+        assert_eq!(lu.rcond(), 0.0);
+        assert!(lu.solve(Vector::new(vec![1.0, 2.0, 3.0])).is_err());
+        assert!(lu.inverse().is_err());
+    }
+
+    #[test]
+    fn test_partial_piv_lu_rcond() {
+        let lu = PartialPivLu::decompose(Matrix::<f64>::identity(5));
+        assert_eq!(lu.rcond(), 1.0);
+
+        let a = test_matrix();
+        let inv = a.inverse().unwrap();
+        let one_norm = |m: &Matrix<f64>| {
+            (0..m.cols())
+                .map(|j| (0..m.rows()).map(|i| m[[i, j]].abs()).sum::<f64>())
+                .fold(0.0, f64::max)
+        };
+        let rcond = 1.0 / (one_norm(&a) * one_norm(&inv));
+
+        let estimate = PartialPivLu::decompose(a).rcond();
+        assert!(estimate >= rcond * (1.0 - 1e-12));
+        assert!(estimate <= 3.0 * rcond);
+    }
+
+    #[test]
+    fn test_partial_piv_lu_solve_refined() {
+        let a = test_matrix();
+        let x_true = Vector::new(vec![1.0, -2.0, 0.5, 3.0]);
+        let b = &a * &x_true;
+
+        let lu = PartialPivLu::decompose(a.clone());
+        let (x, forward_error, backward_error) = lu.solve_refined(&a, b, 5).unwrap();
+
+        let error = (0..4).fold(0.0, |acc: f64, i| acc.max((x[i] - x_true[i]).abs())) / 3.0;
+        assert!(backward_error < 1e-15);
+        assert!(error <= forward_error);
+        assert!(forward_error < 1e-13);
+    }
+
+    #[test]
+    fn test_solve_refined_mixed_precision() {
+        // The 5 x 5 Hilbert matrix has condition number around 5e5.
+        let n = 5;
+        let a = Matrix::from_fn(n, n, |j, i| 1.0 / ((i + j + 1) as f32));
+        let b = Vector::new(vec![1.0f32, -1.0, 2.0, 0.5, 1.5]);
+
+        // The solution of the same system in double precision.
+        let a_f64 = Matrix::from_fn(n, n, |j, i| a[[i, j]] as f64);
+        let b_f64 = Vector::new(b.data().iter().map(|&b_i| b_i as f64).collect::<Vec<_>>());
+        let x_true = a_f64.solve(b_f64).unwrap();
+
+        let relative_error = |x: &Vector<f32>| {
+            let error = (0..n).fold(0.0f64, |acc, i| acc.max((x[i] as f64 - x_true[i]).abs()));
+            error / x_true.data().iter().fold(0.0f64, |acc, x_i| acc.max(x_i.abs()))
+        };
+
+        let unrefined = a.solve(b.clone()).unwrap();
+        let (refined, forward_error, backward_error) = a.solve_refined(b, 10).unwrap();
+
+        assert!(relative_error(&refined) < relative_error(&unrefined) / 10.0);
+        assert!(relative_error(&refined) <= forward_error as f64);
+        assert!(backward_error < 1e-6);
+    }
+
+    #[test]
+    fn test_solve_refined_singular() {
+        let a = Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 4.0]);
+        assert!(a.solve_refined(Vector::new(vec![1.0, 2.0]), 3).is_err());
+    }
+
+    #[test]
+    fn test_solve_refined_empty() {
+        let a = Matrix::<f64>::new(0, 0, vec![]);
+        let (x, forward_error, backward_error) = a.solve_refined(Vector::new(vec![]), 3).unwrap();
+
+        assert_eq!(x.size(), 0);
+        assert_eq!((forward_error, backward_error), (0.0, 0.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_partial_piv_lu_non_square() {
+        let _ = PartialPivLu::decompose(Matrix::new(2, 3, vec![1.0; 6]));
+    }
+}
@@ -0,0 +1,2285 @@
+//! Matrix Decompositions
+//!
+//! References:
+//! 1. [On Matrix Balancing and EigenVector computation]
+//! (http://arxiv.org/pdf/1401.5766v1.pdf), James, Langou and Lowery
+//!
+//! 2. [The QR algorithm for eigen decomposition]
+//! (http://people.inf.ethz.ch/arbenz/ewp/Lnotes/chapter4.pdf)
+//!
+//! 3. [Computation of the SVD]
+//! (http://www.cs.utexas.edu/users/inderjit/public_papers/HLA_SVD.pdf)
+
+use std::any::Any;
+use std::cmp;
+use std::ops::{Mul, Add, Div, Sub, Neg, Range, IndexMut};
+
+use matrix::{Matrix, MatrixSliceMut, BaseMatrix, BaseMatrixMut};
+use matrix::mat_mul::{gemm, gemm_transposed};
+use vector::Vector;
+use utils;
+use error::{Error, ErrorKind};
+
+use libnum::{One, Zero, Float, Signed};
+use epsilon::MachineEpsilon;
+
+mod cholesky;
+mod condition;
+mod generalized;
+mod ldlt;
+mod lstsq;
+mod lu;
+mod pinv;
+mod qr;
+#[cfg(feature = "rand")]
+mod randomized;
+mod schur;
+mod subspace;
+mod symmetric;
+
+pub use self::cholesky::Cholesky;
+pub use self::ldlt::Ldlt;
+pub use self::lstsq::LstsqMethod;
+pub use self::lu::PartialPivLu;
+pub use self::qr::{FullQr, HouseholderQr};
+
+impl<T> Matrix<T>
+    where T: Any + Float
+{
+    /// Cholesky decomposition
+    ///
+    /// Returns the cholesky decomposition of a positive definite matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let m = Matrix::new(3,3, vec![1.0,0.5,0.5,0.5,1.0,0.5,0.5,0.5,1.0]);
+    ///
+    /// let l = m.cholesky();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix is not positive definite.
+    pub fn cholesky(&self) -> Result<Matrix<T>, Error> {
+        let mut l = self.clone();
+        try!(l.cholesky_in_place());
+        Ok(l)
+    }
+
+    /// Computes the Cholesky decomposition of a positive definite
+    /// matrix in place.
+    ///
+    /// On success the matrix is overwritten with the lower triangular
+    /// factor L, such that the original matrix is LL<sup>T</sup>. Only
+    /// the lower triangle of the matrix is used. See
+    /// `MatrixSliceMut::cholesky_in_place` for factorizing part of a
+    /// larger matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let mut m = Matrix::new(2, 2, vec![4.0, 2.0, 2.0, 5.0]);
+    /// m.cholesky_in_place().unwrap();
+    ///
+    /// assert_eq!(*m.data(), vec![2.0, 0.0, 1.0, 2.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix is not positive definite, the contents of the matrix are unspecified.
+    pub fn cholesky_in_place(&mut self) -> Result<(), Error> {
+        self.as_mut_slice().cholesky_in_place()
+    }
+
+    /// Compute the QR decomposition of the matrix.
+    ///
+    /// Returns the tuple (Q,R).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let m = Matrix::new(3,3, vec![1.0,0.5,0.5,0.5,1.0,0.5,0.5,0.5,1.0]);
+    ///
+    /// let (q, r) = m.qr_decomp().unwrap();
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - Cannot compute the QR decomposition.
+    pub fn qr_decomp(self) -> Result<(Matrix<T>, Matrix<T>), Error> {
+        let (m, n) = (self.rows(), self.cols());
+        let qr = HouseholderQr::decompose(self);
+
+        let r_thin = qr.r();
+        let mut r = Matrix::zeros(m, n);
+        for i in 0..r_thin.rows() {
+            for j in i..n {
+                r[[i, j]] = r_thin[[i, j]];
+            }
+        }
+
+        Ok((qr.q_full(), r))
+    }
+
+    /// Compute the QR decomposition of the matrix with column pivoting.
+    ///
+    /// Returns the tuple (Q, R, p), where `p` is a permutation of the column
+    /// indices such that the matrix with its columns reordered by `p` is equal
+    /// to QR. Q is orthogonal and R is upper triangular, with the magnitudes of
+    /// its diagonal entries in non-increasing order.
+    ///
+    /// At each step the remaining column with the largest norm is chosen,
+    /// so the diagonal of R reveals the numerical rank of the matrix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let m = Matrix::new(3, 3, vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 9.0, 7.0, 8.0, 15.0]);
+    ///
+    /// let (q, r, p) = m.clone().qr_decomp_pivoted();
+    ///
+    /// // The largest column comes first
+    /// assert_eq!(p[0], 2);
+    ///
+    /// let qr = q * r;
+    /// let permuted = m.select_cols(&p);
+    /// assert!(qr.data().iter().zip(permuted.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    /// ```
+    pub fn qr_decomp_pivoted(self) -> (Matrix<T>, Matrix<T>, Vec<usize>) {
+        let m = self.rows();
+        let (r, reflectors, permutation) = pivoted_householder_qr(self);
+
+        let mut q = Matrix::<T>::identity(m);
+        for (k, &(ref v, tau)) in reflectors.iter().enumerate() {
+            reflect_cols(&mut q, k, v, tau, 0..m);
+        }
+
+        (q, r, permutation)
+    }
+
+    /// Converts matrix to bidiagonal form
+    ///
+    /// Returns (B, U, V), where B is bidiagonal and `self = U B V_T`.
+    ///
+    /// Note that if `self` has `self.rows() > self.cols()` the matrix will
+    /// be transposed and then reduced - this will lead to a sub-diagonal instead
+    /// of super-diagonal.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix cannot be reduced to bidiagonal form.
+    pub fn bidiagonal_decomp(self) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        self.bidiagonal_reduction(false)
+    }
+
+    /// Converts matrix to bidiagonal form.
+    ///
+    /// If `full` is true the orthogonal matrix on the larger side is
+    /// returned in full instead of being trimmed to the size of B.
+    fn bidiagonal_reduction(mut self, full: bool) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        let mut flipped = false;
+
+        if self.rows < self.cols {
+            flipped = true;
+            self = self.transpose()
+        }
+
+        let m = self.rows;
+        let n = self.cols;
+
+        let mut left_reflectors = Vec::with_capacity(n);
+        let mut right_reflectors = Vec::with_capacity(n.saturating_sub(1));
+
+        for k in 0..n {
+            // Apply householder on the left to kill under diag.
+            let column = (k..m).map(|i| self[[i, k]]).collect::<Vec<_>>();
+            let (h, tau, beta) = householder_vector(&column);
+            reflect_rows(&mut self, k, &h, tau, k + 1..n);
+
+            self[[k, k]] = beta;
+            for i in k + 1..m {
+                self[[i, k]] = T::zero();
+            }
+            left_reflectors.push((h, tau));
+
+            if k + 1 < n {
+                // Apply householder on the right to kill right of super diag.
+                let (h, tau, beta) = householder_vector(&self.data[k * n + k + 1..(k + 1) * n]);
+                reflect_cols(&mut self, k + 1, &h, tau, k + 1..m);
+
+                self[[k, k + 1]] = beta;
+                for j in k + 2..n {
+                    self[[k, j]] = T::zero();
+                }
+                right_reflectors.push((h, tau));
+            }
+        }
+
+        // Accumulate the reflectors, U is trimmed unless the full matrix is requested.
+        let u_cols = if full { m } else { n };
+        let mut u = Matrix::zeros(m, u_cols);
+        for j in 0..u_cols {
+            u[[j, j]] = T::one();
+        }
+        for (k, &(ref h, tau)) in left_reflectors.iter().enumerate().rev() {
+            reflect_rows(&mut u, k, h, tau, 0..u_cols);
+        }
+
+        let mut v = Matrix::identity(n);
+        for (k, &(ref h, tau)) in right_reflectors.iter().enumerate().rev() {
+            reflect_rows(&mut v, k + 1, h, tau, 0..n);
+        }
+
+        // Trim off the zerod blocks.
+        self.data.truncate(n * n);
+        self.rows = n;
+
+        if flipped {
+            Ok((self.transpose(), v, u))
+        } else {
+            Ok((self, u, v))
+        }
+    }
+}
+
+impl<T: Any + Float + MachineEpsilon> Matrix<T> {
+    /// Computes the numerical rank of the matrix using the QR
+    /// decomposition with column pivoting.
+    ///
+    /// The rank is the number of diagonal entries of R whose magnitude is
+    /// greater than `tol`. If `tol` is `None` the tolerance is
+    /// `max(m, n) * eps * |r_00|`, where `eps` is the machine epsilon and
+    /// `r_00` is the largest diagonal entry of R.
+    ///
+    /// This is cheaper than `rank` but less reliable for nearly
+    /// rank-deficient matrices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// // The third column is the sum of the first two.
+    /// let m = Matrix::new(3, 3, vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 9.0, 7.0, 8.0, 15.0]);
+    ///
+    /// assert_eq!(m.qr_rank(None), 2);
+    /// ```
+    pub fn qr_rank(&self, tol: Option<T>) -> usize {
+        let (r, _, _) = pivoted_householder_qr(self.clone());
+        let k = cmp::min(r.rows(), r.cols());
+
+        numerical_rank(self.rows, self.cols, (0..k).map(|i| r[[i, i]].abs()), tol)
+    }
+}
+
+impl<'a, T> MatrixSliceMut<'a, T>
+    where T: Any + Float
+{
+    /// Computes the Cholesky decomposition of a positive definite
+    /// matrix slice in place.
+    ///
+    /// On success the slice is overwritten with the lower triangular
+    /// factor L. Only the lower triangle of the slice is used, and the
+    /// rest of the underlying matrix is untouched, so that a block of a
+    /// larger workspace matrix can be factorized without copying.
+    ///
+    /// This uses a blocked algorithm, where most of the work is done
+    /// by matrix multiplication.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSliceMut};
+    ///
+    /// let mut workspace = Matrix::new(3, 3, vec![1.0, 1.0, 1.0,
+    ///                                            1.0, 4.0, 2.0,
+    ///                                            1.0, 2.0, 5.0]);
+    ///
+    /// {
+    ///     let mut block = MatrixSliceMut::from_matrix(&mut workspace, [1, 1], 2, 2);
+    ///     block.cholesky_in_place().unwrap();
+    /// }
+    ///
+    /// assert_eq!(*workspace.data(), vec![1.0, 1.0, 1.0,
+    ///                                    1.0, 2.0, 0.0,
+    ///                                    1.0, 1.0, 2.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The slice is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix is not positive definite, the contents of the slice are unspecified.
+    pub fn cholesky_in_place(&mut self) -> Result<(), Error> {
+        assert!(self.rows == self.cols,
+                "Matrix must be square for Cholesky decomposition.");
+
+        cholesky_in_place(self).map_err(|_| {
+            Error::new(ErrorKind::DecompFailure, "Matrix is not positive definite.")
+        })
+    }
+
+    /// Computes the QR decomposition of a matrix slice in place.
+    ///
+    /// On completion R is stored in the upper triangle of the slice and
+    /// the Householder vectors defining Q below the diagonal, with their
+    /// leading 1 implicit. The scalar factors of the reflections are
+    /// returned. This is the compact storage used by `HouseholderQr`.
+    /// The rest of the underlying matrix is untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSliceMut};
+    ///
+    /// let mut workspace = Matrix::new(3, 3, vec![9.0f64, 9.0, 9.0,
+    ///                                            3.0, 1.0, 9.0,
+    ///                                            4.0, 2.0, 9.0]);
+    ///
+    /// let tau = {
+    ///     let mut block = MatrixSliceMut::from_matrix(&mut workspace, [1, 0], 2, 2);
+    ///     block.qr_decomp_in_place()
+    /// };
+    ///
+    /// // One reflection per column, the first entry of R has
+    /// // the norm of the first column of the block.
+    /// assert_eq!(tau.len(), 2);
+    /// assert!((workspace[[1, 0]].abs() - 5.0).abs() < 1e-12);
+    /// assert_eq!(workspace[[0, 0]], 9.0);
+    /// assert_eq!(workspace[[2, 2]], 9.0);
+    /// ```
+    pub fn qr_decomp_in_place(&mut self) -> Vec<T> {
+        householder_qr_in_place(self)
+    }
+}
+
+/// The number of columns in each panel of the blocked Cholesky decomposition.
+const CHOLESKY_BLOCK_SIZE: usize = 64;
+
+/// Computes the Cholesky decomposition of the square matrix `a` in place.
+///
+/// Only the lower triangle of `a` is used, and on completion it contains
+/// the factor L while the strict upper triangle is zero. Fails with the
+/// index of the first pivot which is not positive.
+///
+/// This is a right-looking blocked algorithm. Each panel of columns is
+/// factorized with dot products, and the lower triangle of the trailing
+/// submatrix is then updated with matrix multiplications.
+fn cholesky_in_place<T: Any + Float>(a: &mut MatrixSliceMut<T>) -> Result<(), usize> {
+    let n = a.rows;
+
+    let mut block_start = 0;
+    while block_start < n {
+        let block_end = cmp::min(block_start + CHOLESKY_BLOCK_SIZE, n);
+
+        // Factorize the panel, columns before the block have already been applied.
+        for i in block_start..n {
+            for k in block_start..cmp::min(i, block_end) {
+                let sum = (block_start..k).fold(T::zero(), |acc, p| acc + a[[i, p]] * a[[k, p]]);
+                a[[i, k]] = (a[[i, k]] - sum) / a[[k, k]];
+            }
+
+            if i < block_end {
+                let sum = (block_start..i).fold(T::zero(), |acc, p| acc + a[[i, p]] * a[[i, p]]);
+                let pivot = a[[i, i]] - sum;
+                if pivot <= T::zero() || !pivot.is_finite() {
+                    return Err(i);
+                }
+                a[[i, i]] = pivot.sqrt();
+            }
+        }
+
+        // Update the lower triangle of the trailing submatrix, one block column
+        // at a time, A₂₂ = A₂₂ - L₂₁ L₂₁ᵀ.
+        let width = block_end - block_start;
+        let mut col_start = block_end;
+        while col_start < n {
+            let col_end = cmp::min(col_start + CHOLESKY_BLOCK_SIZE, n);
+
+            let l_below = a.sub_slice([col_start, block_start], n - col_start, width);
+            let l_block = a.sub_slice([col_start, block_start], col_end - col_start, width);
+            let mut a_block = a.sub_slice_mut([col_start, col_start], n - col_start, col_end - col_start);
+
+            gemm_transposed(T::zero() - T::one(),
+                            &l_below,
+                            false,
+                            &l_block,
+                            true,
+                            T::one(),
+                            &mut a_block);
+
+            col_start = col_end;
+        }
+
+        block_start = block_end;
+    }
+
+    for i in 0..n {
+        for j in i + 1..n {
+            a[[i, j]] = T::zero();
+        }
+    }
+
+    Ok(())
+}
+
+/// The number of columns in each panel of the blocked LU decomposition.
+const LU_BLOCK_SIZE: usize = 64;
+
+/// Computes the LU decomposition with partial pivoting of the square
+/// matrix `a` in place, such that PA = LU.
+///
+/// On completion U is stored in the upper triangle of `a` and the unit
+/// lower triangular L strictly below the diagonal. Returns the permutation,
+/// where row `i` of PA is row `perm[i]` of A, and whether it is odd.
+///
+/// This is a right-looking blocked algorithm. Each panel of columns is
+/// factorized with row operations, and the trailing submatrix is then
+/// updated with a single matrix multiplication. Zero pivots are skipped,
+/// leaving a zero on the diagonal of U.
+fn lu_in_place<T>(a: &mut MatrixSliceMut<T>) -> (Vec<usize>, bool)
+    where T: Any + Copy + Zero + One + PartialOrd + Neg<Output = T> + Add<T, Output = T> +
+             Sub<T, Output = T> + Mul<T, Output = T> + Div<T, Output = T>
+{
+    let n = a.rows;
+    let abs = |x: T| if x < T::zero() { -x } else { x };
+
+    let mut perm = (0..n).collect::<Vec<_>>();
+    let mut odd_perm = false;
+
+    let mut block_start = 0;
+    while block_start < n {
+        let block_end = cmp::min(block_start + LU_BLOCK_SIZE, n);
+
+        // Factorize the panel, swapping whole rows.
+        for k in block_start..block_end {
+            let mut pivot = k;
+            for i in k + 1..n {
+                if abs(a[[i, k]]) > abs(a[[pivot, k]]) {
+                    pivot = i;
+                }
+            }
+
+            if pivot != k {
+                a.swap_rows(k, pivot);
+                perm.swap(k, pivot);
+                odd_perm = !odd_perm;
+            }
+
+            let pivot_value = a[[k, k]];
+            if pivot_value == T::zero() || k + 1 == n {
+                continue;
+            }
+
+            let pivot_block = a.sub_slice([k, k + 1], 1, block_end - k - 1);
+            let pivot_row = pivot_block.get_row(0).unwrap();
+            let mut lower = a.sub_slice_mut([k + 1, k], n - k - 1, block_end - k);
+
+            for row in lower.iter_rows_mut() {
+                let l = row[0] / pivot_value;
+                row[0] = l;
+
+                if l != T::zero() {
+                    utils::in_place_vec_bin_op(&mut row[1..], pivot_row, |x, &y| {
+                        *x = *x - l * y
+                    });
+                }
+            }
+        }
+
+        if block_end < n {
+            // The block row of U, U₁₂ = L₁₁⁻¹ A₁₂.
+            for i in block_start + 1..block_end {
+                let mut row_i = a.sub_slice_mut([i, block_end], 1, n - block_end);
+
+                for k in block_start..i {
+                    let l = a[[i, k]];
+                    if l != T::zero() {
+                        let row_k = a.sub_slice([k, block_end], 1, n - block_end);
+                        utils::in_place_vec_bin_op(row_i.get_row_mut(0).unwrap(),
+                                                   row_k.get_row(0).unwrap(),
+                                                   |x, &y| *x = *x - l * y);
+                    }
+                }
+            }
+
+            // The trailing submatrix, A₂₂ = A₂₂ - L₂₁ U₁₂.
+            let (block, rest) = (block_end - block_start, n - block_end);
+            let l_21 = a.sub_slice([block_end, block_start], rest, block);
+            let u_12 = a.sub_slice([block_start, block_end], block, rest);
+            let mut a_22 = a.sub_slice_mut([block_end, block_end], rest, rest);
+
+            gemm(T::zero() - T::one(), &l_21, &u_12, T::one(), &mut a_22);
+        }
+
+        block_start = block_end;
+    }
+
+    (perm, odd_perm)
+}
+
+/// Computes an elementary reflector `P = I - tau v vᵀ` such that
+/// `P x = beta e₁`.
+///
+/// Returns the tuple `(v, tau, beta)`. The first element of `v` is always 1.
+/// If `x` is already a multiple of `e₁` then `tau` is zero and `P` is the identity.
+fn householder_vector<T: Float>(x: &[T]) -> (Vec<T>, T, T) {
+    let alpha = x[0];
+    let mut v = x.to_vec();
+    v[0] = T::one();
+
+    let x_norm = utils::dot(&x[1..], &x[1..]).sqrt();
+
+    if x_norm == T::zero() {
+        return (v, T::zero(), alpha);
+    }
+
+    let norm = alpha.hypot(x_norm);
+    let beta = if alpha >= T::zero() { -norm } else { norm };
+    let scale = alpha - beta;
+
+    for v_i in v.iter_mut().skip(1) {
+        *v_i = *v_i / scale;
+    }
+
+    (v, (beta - alpha) / beta, beta)
+}
+
+/// Computes the QR decomposition of `a` in place using Householder
+/// reflections, as in LAPACK's `geqrf`.
+///
+/// On completion R is stored in the upper triangle of `a` and the
+/// Householder vectors below the diagonal, with their leading 1 implicit.
+/// Returns the scalar factors of the reflections.
+fn householder_qr_in_place<T: Float>(a: &mut MatrixSliceMut<T>) -> Vec<T> {
+    let (m, n) = (a.rows(), a.cols());
+    let p = cmp::min(m, n);
+    let mut tau = Vec::with_capacity(p);
+
+    for k in 0..p {
+        let column = (k..m).map(|i| a[[i, k]]).collect::<Vec<_>>();
+        let (v, tau_k, beta) = householder_vector(&column);
+        reflect_rows(a, k, &v, tau_k, k + 1..n);
+
+        a[[k, k]] = beta;
+        for i in k + 1..m {
+            a[[i, k]] = v[i - k];
+        }
+        tau.push(tau_k);
+    }
+
+    tau
+}
+
+/// Reduces `a` to upper triangular form using Householder reflections
+/// with column pivoting.
+///
+/// Returns the triangular factor R, the reflectors `(v, tau)` whose
+/// product in order is Q, and the column permutation.
+fn pivoted_householder_qr<T: Float>(mut a: Matrix<T>) -> (Matrix<T>, Vec<(Vec<T>, T)>, Vec<usize>) {
+    let (m, n) = (a.rows(), a.cols());
+    let mut permutation = (0..n).collect::<Vec<_>>();
+    let mut reflectors = Vec::with_capacity(cmp::min(m, n));
+
+    for k in 0..cmp::min(m, n) {
+        // Pick the remaining column with the largest norm.
+        let mut pivot = k;
+        let mut max_norm = -T::one();
+        for j in k..n {
+            let norm = (k..m).fold(T::zero(), |acc, i| acc + a[[i, j]] * a[[i, j]]);
+            if norm > max_norm {
+                pivot = j;
+                max_norm = norm;
+            }
+        }
+
+        if pivot != k {
+            a.swap_cols(k, pivot);
+            permutation.swap(k, pivot);
+        }
+
+        let column = (k..m).map(|i| a[[i, k]]).collect::<Vec<_>>();
+        let (v, tau, beta) = householder_vector(&column);
+        reflect_rows(&mut a, k, &v, tau, k + 1..n);
+
+        a[[k, k]] = beta;
+        for i in k + 1..m {
+            a[[i, k]] = T::zero();
+        }
+        reflectors.push((v, tau));
+    }
+
+    (a, reflectors, permutation)
+}
+
+/// Applies the reflector `I - tau v vᵀ` from the left to the rows
+/// `k..k + v.len()` of `m`, restricted to the given columns.
+fn reflect_rows<T, M>(m: &mut M, k: usize, v: &[T], tau: T, cols: Range<usize>)
+    where T: Float,
+          M: IndexMut<[usize; 2], Output = T>
+{
+    if tau == T::zero() {
+        return;
+    }
+
+    for j in cols {
+        let mut w = T::zero();
+        for (i, v_i) in v.iter().enumerate() {
+            w = w + *v_i * m[[k + i, j]];
+        }
+
+        w = w * tau;
+        for (i, v_i) in v.iter().enumerate() {
+            m[[k + i, j]] = m[[k + i, j]] - w * *v_i;
+        }
+    }
+}
+
+/// Applies the reflector `I - tau v vᵀ` from the right to the columns
+/// `k..k + v.len()` of `m`, restricted to the given rows.
+fn reflect_cols<T: Float>(m: &mut Matrix<T>, k: usize, v: &[T], tau: T, rows: Range<usize>) {
+    if tau == T::zero() {
+        return;
+    }
+
+    for i in rows {
+        let row = &mut m.data[i * m.cols + k..i * m.cols + k + v.len()];
+        let w = utils::dot(row, v) * tau;
+
+        for (x, v_j) in row.iter_mut().zip(v.iter()) {
+            *x = *x - w * *v_j;
+        }
+    }
+}
+
+/// Applies the rotation `[c s; -s c]` to the rows `i` and `j` of `m`,
+/// restricted to the given columns.
+fn rotate_rows<T: Float>(m: &mut Matrix<T>, i: usize, j: usize, c: T, s: T, cols: Range<usize>) {
+    for k in cols {
+        let (x, y) = (m[[i, k]], m[[j, k]]);
+        m[[i, k]] = c * x + s * y;
+        m[[j, k]] = c * y - s * x;
+    }
+}
+
+/// Applies the transpose of the rotation `[c s; -s c]` from the right
+/// to the columns `i` and `j` of `m`, restricted to the given rows.
+fn rotate_cols<T: Float>(m: &mut Matrix<T>, i: usize, j: usize, c: T, s: T, rows: Range<usize>) {
+    for k in rows {
+        let (x, y) = (m[[k, i]], m[[k, j]]);
+        m[[k, i]] = c * x + s * y;
+        m[[k, j]] = c * y - s * x;
+    }
+}
+
+/// Compute the cos and sin values for the givens rotation.
+///
+/// Returns a tuple (c, s) such that `[c s; -s c] [a; b] = [r; 0]`,
+/// or `(1, 0)` if both `a` and `b` are zero.
+fn givens_rot<T: Float>(a: T, b: T) -> (T, T) {
+    let r = a.hypot(b);
+
+    if r == T::zero() {
+        (T::one(), T::zero())
+    } else {
+        (a / r, b / r)
+    }
+}
+
+/// The default tolerance below which singular values are treated as zero,
+/// `max(m, n) * eps * sigma_max` for an `m` x `n` matrix.
+fn default_rank_tolerance<T: Float + MachineEpsilon>(m: usize, n: usize, sigma_max: T) -> T {
+    T::from(cmp::max(m, n)).unwrap() * <T as MachineEpsilon>::epsilon() * sigma_max
+}
+
+/// Whether a diagonal entry of the triangular factor `r` from the QR
+/// decomposition of an `m` x `n` matrix is negligible, so that the matrix
+/// does not have full rank.
+///
+/// An entry is negligible if it is not greater than the default rank
+/// tolerance, taking the largest diagonal entry in place of `sigma_max`.
+fn has_negligible_diagonal<T: Float + MachineEpsilon>(r: &Matrix<T>, m: usize, n: usize) -> bool {
+    let k = cmp::min(r.rows(), r.cols());
+    let max_diag = (0..k).fold(T::zero(), |acc, i| acc.max(r[[i, i]].abs()));
+    let tol = default_rank_tolerance(m, n, max_diag);
+
+    (0..k).any(|i| r[[i, i]].abs() <= tol)
+}
+
+/// The number of singular values of an `m` x `n` matrix which are greater
+/// than `tol`, or than the default tolerance if `tol` is `None`.
+///
+/// The singular values must be in non-increasing order.
+fn numerical_rank<T, I>(m: usize, n: usize, singular_values: I, tol: Option<T>) -> usize
+    where T: Float + MachineEpsilon,
+          I: IntoIterator<Item = T>
+{
+    let mut singular_values = singular_values.into_iter().peekable();
+    let sigma_max = match singular_values.peek() {
+        Some(&s) => s,
+        None => return 0,
+    };
+
+    let tol = tol.unwrap_or_else(|| default_rank_tolerance(m, n, sigma_max));
+    singular_values.take_while(|&s| s > tol).count()
+}
+
+/// Estimates the 1-norm of the inverse of an `n` x `n` matrix A.
+///
+/// Uses Higham's variant of Hager's method, which only requires a few
+/// solves with A and A<sup>T</sup>. The functions `solve` and
+/// `solve_transpose` must return A<sup>-1</sup>x and A<sup>-T</sup>x.
+/// The estimate is never larger than the true norm.
+fn inverse_norm_estimate<T, F, G>(n: usize, solve: F, solve_transpose: G) -> T
+    where T: Float,
+          F: Fn(Vec<T>) -> Vec<T>,
+          G: Fn(Vec<T>) -> Vec<T>
+{
+    if n == 0 {
+        return T::zero();
+    }
+
+    let n_float = T::from(n).unwrap();
+    let one_norm = |v: &[T]| v.iter().fold(T::zero(), |acc, x| acc + x.abs());
+
+    let mut x = vec![T::one() / n_float; n];
+    let mut estimate = T::zero();
+
+    for iter in 0..5 {
+        let y = solve(x.clone());
+        let y_norm = one_norm(&y);
+        if iter > 0 && y_norm <= estimate {
+            break;
+        }
+        estimate = y_norm;
+
+        let signs = y.iter()
+            .map(|&y_i| if y_i >= T::zero() { T::one() } else { -T::one() })
+            .collect::<Vec<_>>();
+        let z = solve_transpose(signs);
+
+        let (j, z_max) = utils::argmax(&z.iter().map(|z_i| z_i.abs()).collect::<Vec<_>>());
+        if iter > 0 && z_max <= utils::dot(&z, &x) {
+            break;
+        }
+
+        x = vec![T::zero(); n];
+        x[j] = T::one();
+    }
+
+    // An alternative estimate, which guards against the cases where
+    // the iteration above does a poor job.
+    if n > 1 {
+        let b = (0..n)
+            .map(|i| {
+                let value = T::one() + T::from(i).unwrap() / T::from(n - 1).unwrap();
+                if i % 2 == 0 { value } else { -value }
+            })
+            .collect::<Vec<_>>();
+        let y = solve(b);
+        let three = T::from(3).unwrap();
+        let alternative = (T::one() + T::one()) * one_norm(&y) / (three * n_float);
+
+        estimate = estimate.max(alternative);
+    }
+
+    estimate
+}
+
+/// Diagonalizes the upper bidiagonal matrix with diagonal `diag` and
+/// superdiagonal `super_diag` using the implicit Golub-Kahan SVD step.
+///
+/// On completion the absolute values of `diag` are the (unsorted)
+/// singular values. If `vectors` is given the rotations are accumulated
+/// into the columns of U and V respectively, where the bidiagonal matrix
+/// is B = U<sup>T</sup> A V.
+fn bidiagonal_qr<T>(diag: &mut [T],
+                    super_diag: &mut [T],
+                    mut vectors: Option<(&mut Matrix<T>, &mut Matrix<T>)>)
+                    -> Result<(), Error>
+    where T: Float + MachineEpsilon
+{
+    let n = diag.len();
+    if n < 2 {
+        return Ok(());
+    }
+
+    let eps = <T as MachineEpsilon>::epsilon();
+    let two = T::one() + T::one();
+    let norm = diag.iter().chain(super_diag.iter()).fold(T::zero(), |acc, &x| acc.max(x.abs()));
+    let max_iterations = 30 * cmp::max(n, 10);
+    let mut iterations = 0;
+
+    // The final index of the unreduced block
+    let mut hi = n - 1;
+
+    while hi > 0 {
+        for i in 0..hi {
+            if super_diag[i].abs() <= eps * (diag[i].abs() + diag[i + 1].abs()) {
+                super_diag[i] = T::zero();
+            }
+        }
+
+        if super_diag[hi - 1] == T::zero() {
+            hi -= 1;
+            continue;
+        }
+
+        let mut lo = hi - 1;
+        while lo > 0 && super_diag[lo - 1] != T::zero() {
+            lo -= 1;
+        }
+
+        iterations += 1;
+        if iterations > max_iterations {
+            return Err(Error::new(ErrorKind::DecompFailure,
+                                  "Golub-Kahan iteration did not converge."));
+        }
+
+        // A negligible diagonal entry gives a zero singular value. Rotate away
+        // its row and column so that the block splits.
+        if let Some(k) = (lo..hi + 1).find(|&k| diag[k].abs() <= eps * norm) {
+            diag[k] = T::zero();
+
+            if k < hi {
+                let mut f = super_diag[k];
+                super_diag[k] = T::zero();
+                for j in k + 1..hi + 1 {
+                    let (c, s) = givens_rot(diag[j], f);
+                    diag[j] = c * diag[j] + s * f;
+                    if let Some((ref mut u, _)) = vectors {
+                        let rows = u.rows();
+                        rotate_cols(u, j, k, c, s, 0..rows);
+                    }
+                    if j < hi {
+                        f = -s * super_diag[j];
+                        super_diag[j] = c * super_diag[j];
+                    }
+                }
+            }
+
+            if k > lo {
+                let mut f = super_diag[k - 1];
+                super_diag[k - 1] = T::zero();
+                for j in (lo..k).rev() {
+                    let (c, s) = givens_rot(diag[j], f);
+                    diag[j] = c * diag[j] + s * f;
+                    if let Some((_, ref mut v)) = vectors {
+                        let rows = v.rows();
+                        rotate_cols(v, j, k, c, s, 0..rows);
+                    }
+                    if j > lo {
+                        f = -s * super_diag[j - 1];
+                        super_diag[j - 1] = c * super_diag[j - 1];
+                    }
+                }
+            }
+            continue;
+        }
+
+        // The Wilkinson shift from the trailing 2x2 block of BᵀB.
+        let (d_m, d_n, e_m) = (diag[hi - 1], diag[hi], super_diag[hi - 1]);
+        let e_prev = if hi - 1 > lo { super_diag[hi - 2] } else { T::zero() };
+        let t_11 = d_m * d_m + e_prev * e_prev;
+        let t_12 = d_m * e_m;
+        let t_22 = d_n * d_n + e_m * e_m;
+
+        let delta = (t_11 - t_22) / two;
+        let denom = if delta >= T::zero() {
+            delta + delta.hypot(t_12)
+        } else {
+            delta - delta.hypot(t_12)
+        };
+        let mu = if denom == T::zero() {
+            t_22
+        } else {
+            t_22 - t_12 * t_12 / denom
+        };
+
+        let mut y = diag[lo] * diag[lo] - mu;
+        let mut z = diag[lo] * super_diag[lo];
+
+        // Chase the bulge down the unreduced block.
+        for k in lo..hi {
+            let (c, s) = givens_rot(y, z);
+            if k > lo {
+                super_diag[k - 1] = c * y + s * z;
+            }
+
+            let (d_k, e_k, d_k1) = (diag[k], super_diag[k], diag[k + 1]);
+            diag[k] = c * d_k + s * e_k;
+            super_diag[k] = c * e_k - s * d_k;
+            z = s * d_k1;
+            diag[k + 1] = c * d_k1;
+            if let Some((_, ref mut v)) = vectors {
+                let rows = v.rows();
+                rotate_cols(v, k, k + 1, c, s, 0..rows);
+            }
+
+            let (c, s) = givens_rot(diag[k], z);
+            diag[k] = c * diag[k] + s * z;
+
+            let (e_k, d_k1) = (super_diag[k], diag[k + 1]);
+            super_diag[k] = c * e_k + s * d_k1;
+            diag[k + 1] = c * d_k1 - s * e_k;
+            if let Some((ref mut u, _)) = vectors {
+                let rows = u.rows();
+                rotate_cols(u, k, k + 1, c, s, 0..rows);
+            }
+
+            if k + 1 < hi {
+                y = super_diag[k];
+                z = s * super_diag[k + 1];
+                super_diag[k + 1] = c * super_diag[k + 1];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reduces the square matrix `a` to upper Hessenberg form in place
+/// using Householder reflections.
+///
+/// If `q` is given the orthogonal transformations are accumulated into it
+/// from the right.
+fn hessenberg_reduction<T: Float>(a: &mut Matrix<T>, mut q: Option<&mut Matrix<T>>) {
+    let n = a.rows();
+
+    for k in 0..n.saturating_sub(2) {
+        let column = (k + 1..n).map(|i| a[[i, k]]).collect::<Vec<_>>();
+        let (v, tau, beta) = householder_vector(&column);
+
+        reflect_rows(a, k + 1, &v, tau, k..n);
+        reflect_cols(a, k + 1, &v, tau, 0..n);
+        if let Some(ref mut q) = q {
+            reflect_cols(q, k + 1, &v, tau, 0..n);
+        }
+
+        // Enforce the zeros below the subdiagonal
+        a[[k + 1, k]] = beta;
+        for i in k + 2..n {
+            a[[i, k]] = T::zero();
+        }
+    }
+}
+
+/// Ensures that all singular values in the given singular value decomposition
+/// are non-negative, making necessary corrections to the singular vectors.
+///
+/// The SVD is represented by matrices `(b, u, v)`, where `b` is the diagonal matrix
+/// containing the singular values, `u` is the matrix of left singular vectors
+/// and v is the matrix of right singular vectors.
+fn correct_svd_signs<T>(mut b: Matrix<T>,
+                        mut u: Matrix<T>,
+                        mut v: Matrix<T>)
+                        -> (Matrix<T>, Matrix<T>, Matrix<T>)
+    where T: Any + Float + Signed
+{
+
+    // When correcting the signs of the singular vectors, we can choose
+    // to correct EITHER u or v. We make the choice depending on which matrix has the
+    // least number of rows. Later we will need to multiply all elements in columns by
+    // -1, which might be significantly faster in corner cases if we pick the matrix
+    // with the least amount of rows.
+    {
+        let ref mut shortest_matrix = if u.rows() <= v.rows() { &mut u }
+                                      else { &mut v };
+        let column_length = shortest_matrix.rows();
+        let num_singular_values = cmp::min(b.rows(), b.cols());
+
+        for i in 0 .. num_singular_values {
+            if b[[i, i]] < T::zero() {
+                // Swap sign of singular value and column in u
+                b[[i, i]] = b[[i, i]].abs();
+
+                // Access the column as a slice and flip sign
+                let mut column = shortest_matrix.sub_slice_mut([0, i], column_length, 1);
+                column *= -T::one();
+            }
+        }
+    }
+    (b, u, v)
+}
+
+fn sort_svd<T>(mut b: Matrix<T>,
+               u: Matrix<T>,
+               v: Matrix<T>)
+               -> (Matrix<T>, Matrix<T>, Matrix<T>)
+    where T: Any + Float + Signed
+{
+
+    assert!(u.cols() >= b.cols() && v.cols() >= b.cols());
+
+    // This unfortunately incurs two allocations since we have no (simple)
+    // way to iterate over a matrix diagonal, only to copy it into a new Vector
+    let mut indexed_sorted_values: Vec<_> = b.diag().into_vec()
+        .into_iter()
+        .enumerate()
+        .collect();
+
+    // Sorting a vector of indices simultaneously with the singular values
+    // gives us a mapping between old and new (final) column indices.
+    indexed_sorted_values.sort_by(|&(_, ref x), &(_, ref y)|
+        x.partial_cmp(y).expect("All singular values should be finite, and thus sortable.")
+         .reverse()
+    );
+
+    // Set the diagonal elements of the singular value matrix
+    for (i, &(_, value)) in indexed_sorted_values.iter().enumerate() {
+        b[[i, i]] = value;
+    }
+
+    // Reorder the singular vectors alike. Any remaining columns of a full
+    // U or V are kept in place after the sorted ones.
+    let order = indexed_sorted_values.iter().map(|&(i, _)| i).collect::<Vec<_>>();
+    let u_order = order.iter().cloned().chain(order.len()..u.cols()).collect::<Vec<_>>();
+    let v_order = order.iter().cloned().chain(order.len()..v.cols()).collect::<Vec<_>>();
+
+    (b, u.select_cols(&u_order), v.select_cols(&v_order))
+}
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Singular Value Decomposition
+    ///
+    /// Computes the SVD using the Golub-Reinsch algorithm.
+    ///
+    /// Returns Σ, U, V, such that `self` = U Σ V<sup>T</sup>. Σ is a diagonal matrix whose elements
+    /// correspond to the non-negative singular values of the matrix. The singular values are ordered in
+    /// non-increasing order. U and V have orthonormal columns, and each column represents the
+    /// left and right singular vectors for the corresponding singular value in Σ, respectively.
+    ///
+    /// If `self` has M rows and N columns, the dimensions of the returned matrices
+    /// are as follows.
+    ///
+    /// If M >= N:
+    ///
+    /// - `Σ`: N x N
+    /// - `U`: M x N
+    /// - `V`: N x N
+    ///
+    /// If M < N:
+    ///
+    /// - `Σ`: M x M
+    /// - `U`: M x M
+    /// - `V`: N x M
+    ///
+    /// Note: This version of the SVD is sometimes referred to as the 'economy SVD'.
+    /// Use `full_svd` to get square U and V.
+    ///
+    /// # Failures
+    ///
+    /// This function may fail in some cases. The current decomposition whilst being
+    /// efficient is fairly basic. Hopefully the algorithm can be made not to fail in the near future.
+    pub fn svd(self) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        let (b, u, v) = try!(self.svd_unordered(false));
+        Ok(sort_svd(b, u, v))
+    }
+
+    /// Full Singular Value Decomposition
+    ///
+    /// Computes the SVD using the Golub-Reinsch algorithm.
+    ///
+    /// Returns Σ, U, V, such that `self` = U Σ V<sup>T</sup>. Unlike `svd`, U and V
+    /// are square orthogonal matrices. If `self` has M rows and N columns then
+    /// Σ is M x N, U is M x M and V is N x N.
+    ///
+    /// The singular values are on the diagonal of Σ in non-increasing order. The
+    /// columns of U (or V) beyond the number of singular values complete the
+    /// orthonormal basis, these span the left (or right) null space when the
+    /// matrix has full rank.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 2, vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    /// let (s, u, v) = a.clone().full_svd().unwrap();
+    ///
+    /// assert_eq!(s.rows(), 3);
+    /// assert_eq!(s.cols(), 2);
+    /// assert_eq!(u.rows(), 3);
+    /// assert_eq!(u.cols(), 3);
+    ///
+    /// // The last column of U is orthogonal to the columns of A
+    /// let null = u.select_cols(&[2]);
+    /// let product = null.transpose() * &a;
+    /// assert!(product.data().iter().all(|x| x.abs() < 1e-12));
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// This function may fail in some cases. The current decomposition whilst being
+    /// efficient is fairly basic. Hopefully the algorithm can be made not to fail in the near future.
+    pub fn full_svd(self) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        let (m, n) = (self.rows, self.cols);
+        let (b, u, v) = try!(self.svd_unordered(true));
+        let (b, u, v) = sort_svd(b, u, v);
+
+        let mut sigma = Matrix::zeros(m, n);
+        for i in 0..cmp::min(m, n) {
+            sigma[[i, i]] = b[[i, i]];
+        }
+
+        Ok((sigma, u, v))
+    }
+
+    /// Singular values of the matrix.
+    ///
+    /// Returns a Vector of the singular values in non-increasing order. The
+    /// Golub-Kahan iteration is run on the bidiagonal form without accumulating
+    /// the singular vectors, which is much cheaper than computing the full `svd`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(3, 2, vec![3.0f64, 0.0, 0.0, -4.0, 0.0, 0.0]);
+    /// let s = a.singular_values().unwrap();
+    ///
+    /// assert!((s[0] - 4.0).abs() < 1e-14);
+    /// assert!((s[1] - 3.0).abs() < 1e-14);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The Golub-Kahan iteration fails to converge.
+    pub fn singular_values(&self) -> Result<Vector<T>, Error> {
+        let mut a = if self.rows < self.cols {
+            self.transpose()
+        } else {
+            self.clone()
+        };
+        let (m, n) = (a.rows, a.cols);
+
+        // Reduce to upper bidiagonal form, keeping only the diagonals.
+        let mut diag = Vec::with_capacity(n);
+        let mut super_diag = Vec::with_capacity(n.saturating_sub(1));
+        for k in 0..n {
+            let column = (k..m).map(|i| a[[i, k]]).collect::<Vec<_>>();
+            let (v, tau, beta) = householder_vector(&column);
+            reflect_rows(&mut a, k, &v, tau, k + 1..n);
+            diag.push(beta);
+
+            if k + 1 < n {
+                let (v, tau, beta) = householder_vector(&a.data[k * n + k + 1..(k + 1) * n]);
+                reflect_cols(&mut a, k + 1, &v, tau, k + 1..m);
+                super_diag.push(beta);
+            }
+        }
+
+        try!(bidiagonal_qr(&mut diag, &mut super_diag, None));
+
+        let mut values = diag.into_iter().map(|x| x.abs()).collect::<Vec<_>>();
+        values.sort_by(|x, y| {
+            y.partial_cmp(x).expect("All singular values should be finite, and thus sortable.")
+        });
+
+        Ok(Vector::new(values))
+    }
+
+    fn svd_unordered(self, full: bool) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        let (b, u, v) = try!(self.svd_golub_reinsch(full));
+
+        // The Golub-Reinsch implementation sometimes spits out negative singular values,
+        // so we need to correct these.
+        Ok(correct_svd_signs(b, u, v))
+    }
+
+    fn svd_golub_reinsch(mut self, full: bool) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        let mut flipped = false;
+
+        // The algorithm assumes rows > cols. If this is not the case we transpose and fix later.
+        if self.cols > self.rows {
+            self = self.transpose();
+            flipped = true;
+        }
+
+        let n = self.cols;
+
+        // Get the bidiagonal decomposition
+        let (b, mut u, mut v) = try!(self.bidiagonal_reduction(full)
+            .map_err(|_| Error::new(ErrorKind::DecompFailure, "Could not compute SVD.")));
+
+        let mut diag = (0..n).map(|i| b[[i, i]]).collect::<Vec<_>>();
+        let mut super_diag = (1..n).map(|i| b[[i - 1, i]]).collect::<Vec<_>>();
+
+        try!(bidiagonal_qr(&mut diag, &mut super_diag, Some((&mut u, &mut v)))
+            .map_err(|_| Error::new(ErrorKind::DecompFailure, "Could not compute SVD.")));
+
+        let b = Matrix::from_diag(&diag);
+
+        if flipped {
+            Ok((b, v, u))
+        } else {
+            Ok((b, u, v))
+        }
+    }
+
+    /// Returns H, where H is the upper hessenberg form.
+    ///
+    /// If the transformation matrix is also required, you should
+    /// use `upper_hess_decomp`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(4,4,vec![2.,0.,1.,1.,2.,0.,1.,2.,1.,2.,0.,0.,2.,0.,1.,1.]);
+    /// let h = a.upper_hessenberg();
+    ///
+    /// println!("{:?}", h.expect("Could not get upper Hessenberg form.").data());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix cannot be reduced to upper hessenberg form.
+    pub fn upper_hessenberg(mut self) -> Result<Matrix<T>, Error> {
+        let n = self.rows;
+        assert!(n == self.cols,
+                "Matrix must be square to produce upper hessenberg.");
+
+        hessenberg_reduction(&mut self, None);
+        Ok(self)
+    }
+
+    /// Returns (U,H), where H is the upper hessenberg form
+    /// and U is the unitary transform matrix.
+    ///
+    /// The matrix is decomposed as `A = U H U^T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3,3,vec![1.0f64,2.,3.,4.,5.,6.,7.,8.,9.]);
+    ///
+    /// // u is the transform, h is the upper hessenberg form.
+    /// let (u,h) = a.clone().upper_hess_decomp().expect("This matrix should decompose!");
+    ///
+    /// let manual = u.transpose() * a * u;
+    /// assert!(h.data().iter().zip(manual.data().iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix cannot be reduced to upper hessenberg form.
+    pub fn upper_hess_decomp(mut self) -> Result<(Matrix<T>, Matrix<T>), Error> {
+        let n = self.rows;
+        assert!(n == self.cols,
+                "Matrix must be square to produce upper hessenberg.");
+
+        let mut transform = Matrix::identity(n);
+        hessenberg_reduction(&mut self, Some(&mut transform));
+        Ok((transform, self))
+    }
+
+    fn direct_2_by_2_eigenvalues(&self) -> Result<Vec<T>, Error> {
+        // The characteristic polynomial of a 2x2 matrix A is
+        // λ² − (a₁₁ + a₂₂)λ + (a₁₁a₂₂ − a₁₂a₂₁);
+        // the quadratic formula suffices.
+        let tr = self.data[0] + self.data[3];
+        let det = self.data[0] * self.data[3] - self.data[1] * self.data[2];
+
+        let two = T::one() + T::one();
+        let four = two + two;
+
+        let discr = tr * tr - four * det;
+
+        if discr < T::zero() {
+            Err(Error::new(ErrorKind::DecompFailure,
+                           "Matrix has complex eigenvalues. Currently unsupported, sorry!"))
+        } else {
+            let discr_root = discr.sqrt();
+            Ok(vec![(tr - discr_root) / two, (tr + discr_root) / two])
+        }
+
+    }
+
+    /// Eigenvalues of a square matrix.
+    ///
+    /// Returns a Vec of eigenvalues.
+    ///
+    /// Matrices with complex eigenvalues are not supported, use
+    /// `complex_eigenvalues` for these instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(4,4, (1..17).map(|v| v as f64).collect::<Vec<f64>>());
+    /// let e = a.eigenvalues().expect("We should be able to compute these eigenvalues!");
+    /// println!("{:?}", e);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix has complex eigenvalues.
+    /// - Eigenvalues cannot be computed.
+    pub fn eigenvalues(&self) -> Result<Vec<T>, Error> {
+        let n = self.rows();
+        assert!(n == self.cols,
+                "Matrix must be square for eigenvalue computation.");
+
+        match n {
+            1 => Ok(vec![self.data[0]]),
+            2 => self.direct_2_by_2_eigenvalues(),
+            _ => {
+                let eigenvalues = try!(self.complex_eigenvalues());
+                if eigenvalues.iter().any(|e| e.im != T::zero()) {
+                    return Err(Error::new(ErrorKind::DecompFailure,
+                                          "Matrix has complex eigenvalues. Currently \
+                                           unsupported, sorry!"));
+                }
+
+                Ok(eigenvalues.into_iter().map(|e| e.re).collect())
+            }
+        }
+    }
+
+    fn direct_2_by_2_eigendecomp(&self) -> Result<(Vec<T>, Matrix<T>), Error> {
+        let eigenvalues = try!(self.eigenvalues());
+        // Thanks to
+        // http://www.math.harvard.edu/archive/21b_fall_04/exhibits/2dmatrices/index.html
+        // for this characterization—
+        if self.data[2] != T::zero() {
+            let decomp_data = vec![eigenvalues[0] - self.data[3],
+                                   eigenvalues[1] - self.data[3],
+                                   self.data[2],
+                                   self.data[2]];
+            Ok((eigenvalues, Matrix::new(2, 2, decomp_data)))
+        } else if self.data[1] != T::zero() {
+            let decomp_data = vec![self.data[1],
+                                   self.data[1],
+                                   eigenvalues[0] - self.data[0],
+                                   eigenvalues[1] - self.data[0]];
+            Ok((eigenvalues, Matrix::new(2, 2, decomp_data)))
+        } else {
+            Ok((eigenvalues, Matrix::new(2, 2, vec![T::one(), T::zero(), T::zero(), T::one()])))
+        }
+    }
+
+    /// Eigendecomposition of a square matrix.
+    ///
+    /// Returns a Vec of eigenvalues, and a matrix with eigenvectors as the columns.
+    ///
+    /// Matrices with complex eigenvalues are not supported, use
+    /// `complex_eigendecomp` for these instead. For real-symmetric matrices
+    /// `symmetric_eigendecomp` should be preferred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(3,3,vec![3.,2.,4.,2.,0.,2.,4.,2.,3.]);
+    ///
+    /// let (e, m) = a.eigendecomp().expect("We should be able to compute this eigendecomp!");
+    /// println!("{:?}", e);
+    /// println!("{:?}", m.data());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix has complex eigenvalues.
+    /// - The eigen decomposition can not be computed.
+    pub fn eigendecomp(&self) -> Result<(Vec<T>, Matrix<T>), Error> {
+        let n = self.rows();
+        assert!(n == self.cols, "Matrix must be square for eigendecomp.");
+
+        match n {
+            1 => Ok((vec![self.data[0]], Matrix::new(1, 1, vec![T::one()]))),
+            2 => self.direct_2_by_2_eigendecomp(),
+            _ => {
+                let (eigenvalues, eigenvectors) = try!(self.complex_eigendecomp());
+                if eigenvalues.iter().any(|e| e.im != T::zero()) {
+                    return Err(Error::new(ErrorKind::DecompFailure,
+                                          "Matrix has complex eigenvalues. Currently \
+                                           unsupported, sorry!"));
+                }
+
+                let eigenvectors = eigenvectors.into_vec().into_iter().map(|v| v.re).collect::<Vec<_>>();
+                Ok((eigenvalues.into_iter().map(|e| e.re).collect(),
+                    Matrix::new(n, n, eigenvectors)))
+            }
+        }
+    }
+}
+
+impl<T> Matrix<T> where T: Any + Copy + One + Zero + Neg<Output=T> +
+                           Add<T, Output=T> + Mul<T, Output=T> +
+                           Sub<T, Output=T> + Div<T, Output=T> +
+                           PartialOrd {
+
+/// Computes L, U, and P for LUP decomposition.
+///
+/// Returns L,U, and P respectively, such that PA = LU.
+///
+/// The decomposition uses partial pivoting and a blocked algorithm, where
+/// most of the work is done by matrix multiplication. To solve several
+/// systems with the same matrix use `PartialPivLu` instead, which avoids
+/// forming L, U and P separately.
+///
+/// # Examples
+///
+/// ```
+/// use rulinalg::matrix::Matrix;
+///
+/// let a = Matrix::new(3,3, vec![1.0,2.0,0.0,
+///                               0.0,3.0,4.0,
+///                               5.0, 1.0, 2.0]);
+///
+/// let (l,u,p) = a.lup_decomp().expect("This matrix should decompose!");
+/// ```
+///
+/// # Panics
+///
+/// - Matrix is not square.
+///
+/// # Failures
+///
+/// - Matrix cannot be LUP decomposed.
+    pub fn lup_decomp(&self) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        self.lup_decomp_cloned()
+    }
+}
+
+impl<'a, T> MatrixSliceMut<'a, T>
+    where T: Any + Copy + One + Zero + Neg<Output = T> + Add<T, Output = T> +
+             Mul<T, Output = T> + Sub<T, Output = T> + Div<T, Output = T> + PartialOrd
+{
+    /// Computes the LUP decomposition of a matrix slice in place.
+    ///
+    /// On success U is stored in the upper triangle of the slice and the
+    /// unit lower triangular L strictly below the diagonal, such that
+    /// PA = LU. The permutation is returned as a vector, where row `i`
+    /// of PA is row `perm[i]` of A. The rest of the underlying matrix is
+    /// untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSliceMut};
+    ///
+    /// let mut workspace = Matrix::new(3, 3, vec![9.0, 9.0, 9.0,
+    ///                                            9.0, 1.0, 2.0,
+    ///                                            9.0, 4.0, 4.0]);
+    ///
+    /// let perm = {
+    ///     let mut block = MatrixSliceMut::from_matrix(&mut workspace, [1, 1], 2, 2);
+    ///     block.lup_decomp_in_place().unwrap()
+    /// };
+    ///
+    /// assert_eq!(perm, vec![1, 0]);
+    /// assert_eq!(*workspace.data(), vec![9.0, 9.0, 9.0,
+    ///                                    9.0, 4.0, 4.0,
+    ///                                    9.0, 0.25, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The slice is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix cannot be LUP decomposed, the contents of the slice are unspecified.
+    pub fn lup_decomp_in_place(&mut self) -> Result<Vec<usize>, Error> {
+        let n = self.rows;
+        assert!(n == self.cols, "Matrix must be square for LUP decomposition.");
+
+        let (perm, _) = lu_in_place(self);
+
+        if (0..n).any(|i| self[[i, i]] == T::zero()) {
+            return Err(Error::new(ErrorKind::DivByZero,
+                                  "Singular matrix found in LUP decomposition. \
+                                   A value in the diagonal of U == 0.0."));
+        }
+
+        Ok(perm)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use std::cmp;
+
+    use matrix::{Matrix, BaseMatrix};
+    use vector::Vector;
+    use super::{sort_svd, HouseholderQr};
+
+    /// Asserts that two matrices have the same size and that their
+    /// entries differ by less than `tol`.
+    pub fn assert_close(a: &Matrix<f64>, b: &Matrix<f64>, tol: f64) {
+        assert_eq!((a.rows(), a.cols()), (b.rows(), b.cols()));
+        assert!(a.data().iter().zip(b.data().iter()).all(|(x, y)| (x - y).abs() < tol));
+    }
+
+    fn validate_bidiag(mat: &Matrix<f64>,
+                       b: &Matrix<f64>,
+                       u: &Matrix<f64>,
+                       v: &Matrix<f64>,
+                       upper: bool) {
+        for (idx, row) in b.iter_rows().enumerate() {
+            let pair_start = if upper {
+                idx
+            } else {
+                idx.saturating_sub(1)
+            };
+            assert!(!row.iter().take(pair_start).any(|&x| x > 1e-10));
+            assert!(!row.iter().skip(pair_start + 2).any(|&x| x > 1e-10));
+        }
+
+        let recovered = u * b * v.transpose();
+
+        assert_eq!(recovered.rows(), mat.rows());
+        assert_eq!(recovered.cols(), mat.cols());
+
+        assert!(!mat.data()
+            .iter()
+            .zip(recovered.data().iter())
+            .any(|(&x, &y)| (x - y).abs() > 1e-10));
+    }
+
+    #[test]
+    fn test_bidiagonal_square() {
+        let mat = Matrix::new(5,
+                              5,
+                              vec![1f64, 2.0, 3.0, 4.0, 5.0, 2.0, 4.0, 1.0, 2.0, 1.0, 3.0, 1.0,
+                                   7.0, 1.0, 1.0, 4.0, 2.0, 1.0, -1.0, 3.0, 5.0, 1.0, 1.0, 3.0,
+                                   2.0]);
+        let (b, u, v) = mat.clone().bidiagonal_decomp().unwrap();
+        validate_bidiag(&mat, &b, &u, &v, true);
+    }
+
+    #[test]
+    fn test_bidiagonal_non_square() {
+        let mat = Matrix::new(5,
+                              3,
+                              vec![1f64, 2.0, 3.0, 4.0, 5.0, 2.0, 4.0, 1.0, 2.0, 1.0, 3.0, 1.0,
+                                   7.0, 1.0, 1.0]);
+        let (b, u, v) = mat.clone().bidiagonal_decomp().unwrap();
+        validate_bidiag(&mat, &b, &u, &v, true);
+
+        let mat = Matrix::new(3,
+                              5,
+                              vec![1f64, 2.0, 3.0, 4.0, 5.0, 2.0, 4.0, 1.0, 2.0, 1.0, 3.0, 1.0,
+                                   7.0, 1.0, 1.0]);
+        let (b, u, v) = mat.clone().bidiagonal_decomp().unwrap();
+        validate_bidiag(&mat, &b, &u, &v, false);
+    }
+
+    fn validate_svd(mat: &Matrix<f64>, b: &Matrix<f64>, u: &Matrix<f64>, v: &Matrix<f64>) {
+        // b is diagonal (the singular values)
+        for (idx, row) in b.iter_rows().enumerate() {
+            assert!(!row.iter().take(idx).any(|&x| x > 1e-10));
+            assert!(!row.iter().skip(idx + 1).any(|&x| x > 1e-10));
+            // Assert non-negativity of diagonal elements
+            assert!(row[idx] >= 0.0);
+        }
+
+        let recovered = u * b * v.transpose();
+
+        assert_eq!(recovered.rows(), mat.rows());
+        assert_eq!(recovered.cols(), mat.cols());
+
+        assert!(!mat.data()
+            .iter()
+            .zip(recovered.data().iter())
+            .any(|(&x, &y)| (x - y).abs() > 1e-10));
+
+        // The transposition is due to the fact that there does not exist
+        // any column iterators at the moment, and we need to simultaneously iterate
+        // over the columns. Once they do exist, we should rewrite
+        // the below iterators to use iter_cols() or whatever instead.
+        let ref u_transposed = u.transpose();
+        let ref v_transposed = v.transpose();
+        let ref mat_transposed = mat.transpose();
+
+        let mut singular_triplets = u_transposed.iter_rows().zip(b.diag().into_iter()).zip(v_transposed.iter_rows())
+            // chained zipping results in nested tuple. Flatten it.
+            .map(|((u_col, singular_value), v_col)| (Vector::new(u_col), singular_value, Vector::new(v_col)));
+
+        assert!(singular_triplets.by_ref()
+            // For a matrix M, each singular value σ and left and right singular vectors u and v respectively
+            // satisfy M v = σ u, so we take the difference
+            .map(|(ref u, sigma, ref v)| mat * v - u * sigma)
+            .flat_map(|v| v.into_vec().into_iter())
+            .all(|x| x.abs() < 1e-10));
+
+        assert!(singular_triplets.by_ref()
+            // For a matrix M, each singular value σ and left and right singular vectors u and v respectively
+            // satisfy M_transposed u = σ v, so we take the difference
+            .map(|(ref u, sigma, ref v)| mat_transposed * u - v * sigma)
+            .flat_map(|v| v.into_vec().into_iter())
+            .all(|x| x.abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_sort_svd() {
+        let u = Matrix::new(2, 3, vec![1.0, 2.0, 3.0,
+                                       4.0, 5.0, 6.0]);
+        let b = Matrix::new(3, 3, vec![4.0, 0.0, 0.0,
+                                       0.0, 8.0, 0.0,
+                                       0.0, 0.0, 2.0]);
+        let v = Matrix::new(3, 3, vec![21.0, 22.0, 23.0,
+                                       24.0, 25.0, 26.0,
+                                       27.0, 28.0, 29.0]);
+        let (b, u, v) = sort_svd(b, u, v);
+
+        assert_eq!(b.data(), &vec![8.0, 0.0, 0.0,
+                                  0.0, 4.0, 0.0,
+                                  0.0, 0.0, 2.0]);
+        assert_eq!(u.data(), &vec![2.0, 1.0, 3.0,
+                                  5.0, 4.0, 6.0]);
+        assert_eq!(v.data(), &vec![22.0, 21.0, 23.0,
+                                  25.0, 24.0, 26.0,
+                                  28.0, 27.0, 29.0]);
+
+    }
+
+    #[test]
+    fn test_svd_tall_matrix() {
+        // Note: This matrix is not arbitrary. It has been constructed specifically so that
+        // the "natural" order of the singular values it not sorted by default.
+        let mat = Matrix::new(5, 4,
+                              vec![ 3.61833700244349288, -3.28382346228211697,  1.97968027781346501, -0.41869628192662156,
+                                    3.96046289599926427,  0.70730060716580723, -2.80552479438772817, -1.45283286109873933,
+                                    1.44435028724617442,  1.27749196276785826, -1.09858397535426366, -0.03159619816434689,
+                                    1.13455445826500667,  0.81521390274755756,  3.99123446373437263, -2.83025703359666192,
+                                   -3.30895752093770579, -0.04979044289857298,  3.03248594516832792,  3.85962479743330977]);
+        let (b, u, v) = mat.clone().svd().unwrap();
+
+        let expected_values = vec![8.0, 6.0, 4.0, 2.0];
+
+        validate_svd(&mat, &b, &u, &v);
+
+        // Assert the singular values are what we expect
+        assert!(expected_values.iter()
+            .zip(b.diag().data().iter())
+            .all(|(expected, actual)| (expected - actual).abs() < 1e-14));
+    }
+
+    #[test]
+    fn test_svd_short_matrix() {
+        // Note: This matrix is not arbitrary. It has been constructed specifically so that
+        // the "natural" order of the singular values it not sorted by default.
+        let mat = Matrix::new(4, 5,
+                              vec![ 3.61833700244349288,  3.96046289599926427,  1.44435028724617442,  1.13455445826500645, -3.30895752093770579,
+                                   -3.28382346228211697,  0.70730060716580723,  1.27749196276785826,  0.81521390274755756, -0.04979044289857298,
+                                    1.97968027781346545, -2.80552479438772817, -1.09858397535426366,  3.99123446373437263,  3.03248594516832792,
+                                   -0.41869628192662156, -1.45283286109873933, -0.03159619816434689, -2.83025703359666192,  3.85962479743330977]);
+        let (b, u, v) = mat.clone().svd().unwrap();
+
+        let expected_values = vec![8.0, 6.0, 4.0, 2.0];
+
+        validate_svd(&mat, &b, &u, &v);
+
+        // Assert the singular values are what we expect
+        assert!(expected_values.iter()
+            .zip(b.diag().data().iter())
+            .all(|(expected, actual)| (expected - actual).abs() < 1e-14));
+    }
+
+    #[test]
+    fn test_svd_square_matrix() {
+        let mat = Matrix::new(5, 5,
+                              vec![1.0,  2.0,  3.0,  4.0,  5.0,
+                                   2.0,  4.0,  1.0,  2.0,  1.0,
+                                   3.0,  1.0,  7.0,  1.0,  1.0,
+                                   4.0,  2.0,  1.0, -1.0,  3.0,
+                                   5.0,  1.0,  1.0,  3.0,  2.0]);
+
+        let expected_values = vec![ 12.1739747429271112,   5.2681047320525831,   4.4942269799769843,
+                                     2.9279675877385123,   2.8758200827412224];
+
+        let (b, u, v) = mat.clone().svd().unwrap();
+        validate_svd(&mat, &b, &u, &v);
+
+        // Assert the singular values are what we expect
+        assert!(expected_values.iter()
+            .zip(b.diag().data().iter())
+            .all(|(expected, actual)| (expected - actual).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_svd_rank_deficient_matrix() {
+        // The third column is the sum of the first two, and the
+        // first diagonal entry of the bidiagonal form is zero.
+        let mat = Matrix::new(4, 3,
+                              vec![0.0, 1.0, 1.0,
+                                   0.0, 2.0, 2.0,
+                                   1.0, 0.0, 1.0,
+                                   0.0, 0.0, 0.0]);
+
+        let (b, u, v) = mat.clone().svd().unwrap();
+        validate_svd(&mat, &b, &u, &v);
+        assert!(b[[2, 2]] < 1e-14);
+
+        let (b, u, v) = Matrix::<f64>::zeros(3, 2).svd().unwrap();
+        validate_svd(&Matrix::zeros(3, 2), &b, &u, &v);
+    }
+
+    #[test]
+    fn test_svd_single_column() {
+        let mat = Matrix::new(25, 1, (0..25).map(|x| x as f64).collect::<Vec<_>>());
+
+        let (b, u, v) = mat.clone().svd().unwrap();
+        validate_svd(&mat, &b, &u, &v);
+    }
+
+    #[test]
+    fn test_singular_values() {
+        let mat = Matrix::new(5, 4,
+                              vec![ 3.61833700244349288f64, -3.28382346228211697,  1.97968027781346501, -0.41869628192662156,
+                                    3.96046289599926427,  0.70730060716580723, -2.80552479438772817, -1.45283286109873933,
+                                    1.44435028724617442,  1.27749196276785826, -1.09858397535426366, -0.03159619816434689,
+                                    1.13455445826500667,  0.81521390274755756,  3.99123446373437263, -2.83025703359666192,
+                                   -3.30895752093770579, -0.04979044289857298,  3.03248594516832792,  3.85962479743330977]);
+
+        let expected_values = vec![8.0, 6.0, 4.0, 2.0];
+
+        let values = mat.singular_values().unwrap();
+        assert!(expected_values.iter().zip(values.data().iter()).all(|(x, y)| (x - y).abs() < 1e-13));
+
+        let values = mat.transpose().singular_values().unwrap();
+        assert!(expected_values.iter().zip(values.data().iter()).all(|(x, y)| (x - y).abs() < 1e-13));
+    }
+
+    #[test]
+    fn test_singular_values_rank_deficient() {
+        let mat = Matrix::new(3, 3, vec![1.0f64, 2.0, 3.0, 2.0, 4.0, 6.0, 1.0, 0.0, 1.0]);
+        let values = mat.singular_values().unwrap();
+
+        // The sum of the squared singular values is the squared Frobenius norm
+        let sum_squares = values.data().iter().fold(0.0, |acc, x| acc + x * x);
+        assert!((sum_squares - 72.0).abs() < 1e-12);
+        assert!(values[2] < 1e-12);
+    }
+
+    fn validate_full_svd(mat: &Matrix<f64>, b: &Matrix<f64>, u: &Matrix<f64>, v: &Matrix<f64>) {
+        let (m, n) = (mat.rows(), mat.cols());
+
+        assert_eq!((b.rows(), b.cols()), (m, n));
+        assert_eq!((u.rows(), u.cols()), (m, m));
+        assert_eq!((v.rows(), v.cols()), (n, n));
+
+        // b is diagonal with non-increasing, non-negative values
+        for i in 0..m {
+            for j in 0..n {
+                if i != j {
+                    assert_eq!(b[[i, j]], 0.0);
+                }
+            }
+        }
+        let values = b.diag().into_vec();
+        assert!(values.windows(2).all(|w| w[0] >= w[1]));
+        assert!(values.iter().all(|&x| x >= 0.0));
+
+        // u and v are orthogonal
+        let utu = u.transpose() * u;
+        let vtv = v.transpose() * v;
+        assert!(utu.data().iter().zip(Matrix::<f64>::identity(m).data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+        assert!(vtv.data().iter().zip(Matrix::<f64>::identity(n).data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        let recovered = u * b * v.transpose();
+        assert!(mat.data().iter().zip(recovered.data().iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_full_svd_tall_matrix() {
+        let mat = Matrix::new(5, 3,
+                              vec![ 1.0,  2.0,  3.0,
+                                    4.0,  5.0,  6.0,
+                                   -1.0,  0.0,  2.0,
+                                    3.0, -2.0,  1.0,
+                                    0.5,  1.0, -1.0]);
+        let (b, u, v) = mat.clone().full_svd().unwrap();
+        validate_full_svd(&mat, &b, &u, &v);
+
+        // The trailing columns of u span the left null space
+        let null_space = u.select_cols(&[3, 4]);
+        let product = null_space.transpose() * &mat;
+        assert!(product.data().iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_full_svd_short_matrix() {
+        let mat = Matrix::new(2, 4,
+                              vec![ 1.0,  2.0,  3.0, 4.0,
+                                   -1.0,  0.0,  2.0, 1.0]);
+        let (b, u, v) = mat.clone().full_svd().unwrap();
+        validate_full_svd(&mat, &b, &u, &v);
+
+        // The trailing columns of v span the null space
+        let null_space = v.select_cols(&[2, 3]);
+        let product = &mat * null_space;
+        assert!(product.data().iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_full_svd_square_matrix() {
+        let mat = Matrix::new(3, 3, vec![2.0, 0.0, 1.0, -1.0, 3.0, 0.0, 0.0, 1.0, 4.0]);
+        let (b, u, v) = mat.clone().full_svd().unwrap();
+        validate_full_svd(&mat, &b, &u, &v);
+
+        let (b_econ, _, _) = mat.svd().unwrap();
+        assert!(b.diag().data().iter().zip(b_econ.diag().data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    }
+
+    fn validate_pivoted_qr(mat: &Matrix<f64>, q: &Matrix<f64>, r: &Matrix<f64>, p: &[usize]) {
+        let (m, n) = (mat.rows(), mat.cols());
+
+        let qtq = q.transpose() * q;
+        assert!(qtq.data().iter().zip(Matrix::<f64>::identity(m).data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        for i in 0..m {
+            for j in 0..cmp::min(i, n) {
+                assert_eq!(r[[i, j]], 0.0);
+            }
+        }
+
+        let diag = r.diag().into_vec();
+        assert!(diag.windows(2).all(|w| w[0].abs() >= w[1].abs()));
+
+        let qr = q * r;
+        let permuted = mat.select_cols(p);
+        assert!(qr.data().iter().zip(permuted.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_qr_decomp_pivoted() {
+        let mat = Matrix::new(4, 3, vec![1.0, 2.0, 3.0,
+                                         4.0, 5.0, 6.0,
+                                         7.0, 8.0, 10.0,
+                                         -1.0, 0.0, 2.0]);
+        let (q, r, p) = mat.clone().qr_decomp_pivoted();
+        validate_pivoted_qr(&mat, &q, &r, &p);
+
+        let mut sorted = p.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_qr_decomp_pivoted_wide() {
+        let mat = Matrix::new(2, 4, vec![1.0, -2.0, 0.5, 3.0,
+                                         0.0, 4.0, 1.0, -1.0]);
+        let (q, r, p) = mat.clone().qr_decomp_pivoted();
+        validate_pivoted_qr(&mat, &q, &r, &p);
+    }
+
+    #[test]
+    fn test_qr_rank() {
+        // Rank 2: the last two columns are combinations of the first two
+        let mat = Matrix::new(4, 4, vec![1.0, 2.0, 3.0, -1.0,
+                                         4.0, 5.0, 9.0, -1.0,
+                                         7.0, 8.0, 15.0, -1.0,
+                                         -1.0, 0.0, -1.0, -1.0]);
+        assert_eq!(mat.qr_rank(None), 2);
+
+        let (q, r, p) = mat.clone().qr_decomp_pivoted();
+        validate_pivoted_qr(&mat, &q, &r, &p);
+
+        // The tolerance is absolute, not relative to |r_00|.
+        assert_eq!(mat.qr_rank(Some(1e-12)), 2);
+        assert_eq!(mat.qr_rank(Some(1e3)), 0);
+
+        assert_eq!(Matrix::<f64>::identity(3).qr_rank(None), 3);
+        assert_eq!(Matrix::<f64>::zeros(3, 2).qr_rank(None), 0);
+        assert_eq!(Matrix::<f64>::zeros(0, 2).qr_rank(None), 0);
+    }
+
+    #[test]
+    fn test_1_by_1_matrix_eigenvalues() {
+        let a = Matrix::new(1, 1, vec![3.]);
+        assert_eq!(vec![3.], a.eigenvalues().unwrap());
+    }
+
+    #[test]
+    fn test_2_by_2_matrix_eigenvalues() {
+        let a = Matrix::new(2, 2, vec![1., 2., 3., 4.]);
+        // characteristic polynomial is λ² − 5λ − 2 = 0
+        assert_eq!(vec![(5. - (33.0f32).sqrt()) / 2., (5. + (33.0f32).sqrt()) / 2.],
+                   a.eigenvalues().unwrap());
+    }
+
+    #[test]
+    fn test_2_by_2_matrix_zeros_eigenvalues() {
+        let a = Matrix::new(2, 2, vec![0.; 4]);
+        // characteristic polynomial is λ² = 0
+        assert_eq!(vec![0.0, 0.0], a.eigenvalues().unwrap());
+    }
+
+    #[test]
+    fn test_2_by_2_matrix_complex_eigenvalues() {
+        // This test currently fails - complex eigenvalues would be nice though!
+        let a = Matrix::new(2, 2, vec![1.0, -3.0, 1.0, 1.0]);
+        // characteristic polynomial is λ² − λ + 4 = 0
+
+        // Decomposition will fail
+        assert!(a.eigenvalues().is_err());
+    }
+
+    #[test]
+    fn test_2_by_2_matrix_eigendecomp() {
+        let a = Matrix::new(2, 2, vec![20., 4., 20., 16.]);
+        let (eigenvals, eigenvecs) = a.eigendecomp().unwrap();
+
+        let lambda_1 = eigenvals[0];
+        let lambda_2 = eigenvals[1];
+
+        let v1 = Vector::new(vec![eigenvecs[[0, 0]], eigenvecs[[1, 0]]]);
+        let v2 = Vector::new(vec![eigenvecs[[0, 1]], eigenvecs[[1, 1]]]);
+
+        let epsilon = 0.00001;
+        assert!((&a * &v1 - &v1 * lambda_1).into_vec().iter().all(|&c| c < epsilon));
+        assert!((&a * &v2 - &v2 * lambda_2).into_vec().iter().all(|&c| c < epsilon));
+    }
+
+    #[test]
+    fn test_3_by_3_eigenvals() {
+        let a = Matrix::new(3, 3, vec![17f64, 22., 27., 22., 29., 36., 27., 36., 45.]);
+
+        let eigs = a.eigenvalues().unwrap();
+
+        let eig_1 = 90.4026;
+        let eig_2 = 0.5973;
+        let eig_3 = 0.0;
+
+        assert!(eigs.iter().any(|x| (x - eig_1).abs() < 1e-4));
+        assert!(eigs.iter().any(|x| (x - eig_2).abs() < 1e-4));
+        assert!(eigs.iter().any(|x| (x - eig_3).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_5_by_5_eigenvals() {
+        let a = Matrix::new(5,
+                            5,
+                            vec![1f64, 2.0, 3.0, 4.0, 5.0, 2.0, 4.0, 1.0, 2.0, 1.0, 3.0, 1.0,
+                                 7.0, 1.0, 1.0, 4.0, 2.0, 1.0, -1.0, 3.0, 5.0, 1.0, 1.0, 3.0, 2.0]);
+
+        let eigs = a.eigenvalues().unwrap();
+
+        let eig_1 = 12.174;
+        let eig_2 = 5.2681;
+        let eig_3 = -4.4942;
+        let eig_4 = 2.9279;
+        let eig_5 = -2.8758;
+
+        assert!(eigs.iter().any(|x| (x - eig_1).abs() < 1e-4));
+        assert!(eigs.iter().any(|x| (x - eig_2).abs() < 1e-4));
+        assert!(eigs.iter().any(|x| (x - eig_3).abs() < 1e-4));
+        assert!(eigs.iter().any(|x| (x - eig_4).abs() < 1e-4));
+        assert!(eigs.iter().any(|x| (x - eig_5).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_3_by_3_nonsymmetric_eigendecomp() {
+        let a = Matrix::new(3, 3, vec![2f64, 1.0, 0.0, 4.0, 3.0, 1.0, 0.0, 2.0, 5.0]);
+
+        let (eigenvals, eigenvecs) = a.eigendecomp().unwrap();
+
+        for (k, lambda) in eigenvals.into_iter().enumerate() {
+            let v = Vector::new(vec![eigenvecs[[0, k]], eigenvecs[[1, k]], eigenvecs[[2, k]]]);
+            assert!((&a * &v - &v * lambda).into_vec().iter().all(|c| c.abs() < 1e-10));
+        }
+    }
+
+    #[test]
+    fn test_3_by_3_complex_eigenvalues_fail() {
+        // A rotation about the z axis has eigenvalues 1 and ±i.
+        let a = Matrix::new(3, 3, vec![0f64, -1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0]);
+
+        assert!(a.eigenvalues().is_err());
+        assert!(a.eigendecomp().is_err());
+    }
+
+    #[test]
+    fn test_upper_hess_decomp() {
+        let a = Matrix::new(4,
+                            4,
+                            vec![2f64, 0.0, 1.0, 1.0, 2.0, 0.0, 1.0, 2.0, 1.0, 2.0, 0.0, 0.0, 2.0,
+                                 0.0, 1.0, 1.0]);
+
+        let (u, h) = a.clone().upper_hess_decomp().unwrap();
+
+        for i in 2..4 {
+            for j in 0..i - 1 {
+                assert_eq!(h[[i, j]], 0.0);
+            }
+        }
+        let b = &u * &h * u.transpose();
+        assert!(a.data().iter().zip(b.data().iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+        assert_eq!(h, a.upper_hessenberg().unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_cholesky() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+
+        let _ = a.cholesky();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_upper_hessenberg() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+
+        let _ = a.upper_hessenberg();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_upper_hess_decomp() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+
+        let _ = a.upper_hess_decomp();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_eigenvalues() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+
+        let _ = a.eigenvalues();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_eigendecomp() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+
+        let _ = a.eigendecomp();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_lup_decomp() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+
+        let _ = a.lup_decomp();
+    }
+
+    #[test]
+    fn test_lup_decomp() {
+        use error::ErrorKind;
+        let a: Matrix<f64> = matrix!(
+            1., 2., 3., 4.;
+            0., 0., 0., 0.;
+            0., 0., 0., 0.;
+            0., 0., 0., 0.
+        );
+
+        match a.lup_decomp() {
+            Err(e) => assert!(*e.kind() == ErrorKind::DivByZero),
+            Ok(_) => panic!()
+        }
+    }
+
+    #[test]
+    fn test_cholesky_blocked() {
+        // Large enough to use several blocks, with a size which is not a multiple of the block size.
+        let n = 150;
+        let mut state = 54321u32;
+        let b = Matrix::from_fn(n, n, |_, _| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as f64 / 65536.0 - 0.5
+        });
+        let a = &b * b.transpose() + Matrix::identity(n);
+
+        let l = a.cholesky().unwrap();
+        for i in 0..n {
+            assert!(l[[i, i]] > 0.0);
+            assert!(l.data()[i * n + i + 1..(i + 1) * n].iter().all(|&x| x == 0.0));
+        }
+
+        let diff = &l * l.transpose() - &a;
+        assert!(diff.data().iter().all(|x| x.abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_cholesky_in_place_slice() {
+        use matrix::MatrixSliceMut;
+
+        // The upper triangle of the block is never read.
+        let mut workspace = Matrix::new(4, 4, vec![-1.0, -1.0, -1.0, -1.0,
+                                                   -1.0, 4.0, 99.0, 99.0,
+                                                   -1.0, 2.0, 10.0, 99.0,
+                                                   -1.0, -2.0, 2.0, 5.0]);
+
+        MatrixSliceMut::from_matrix(&mut workspace, [1, 1], 3, 3).cholesky_in_place().unwrap();
+
+        assert_eq!(*workspace.data(), vec![-1.0, -1.0, -1.0, -1.0,
+                                           -1.0, 2.0, 0.0, 0.0,
+                                           -1.0, 1.0, 3.0, 0.0,
+                                           -1.0, -1.0, 1.0, 1.7320508075688772]);
+    }
+
+    #[test]
+    fn test_cholesky_not_positive_definite() {
+        // Only the last pivot is negative.
+        let a = Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 3.0]);
+        assert!(a.cholesky().is_err());
+
+        let mut b = a.clone();
+        assert!(b.cholesky_in_place().is_err());
+    }
+
+    #[test]
+    fn test_lup_decomp_blocked() {
+        // Large enough to use several blocks, with a size which is not a multiple of the block size.
+        let n = 150;
+        let mut state = 12345u32;
+        let a = Matrix::from_fn(n, n, |_, _| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as f64 / 65536.0 - 0.5
+        });
+        let (l, u, p) = a.lup_decomp().unwrap();
+
+        for i in 0..n {
+            assert_eq!(l[[i, i]], 1.0);
+            for j in i + 1..n {
+                assert_eq!(l[[i, j]], 0.0);
+                assert_eq!(u[[j, i]], 0.0);
+            }
+            assert!(l.data()[i * n..(i + 1) * n].iter().all(|x| x.abs() <= 1.0));
+        }
+
+        let diff = &p * &a - &l * &u;
+        assert!(diff.data().iter().all(|x| x.abs() < 1e-10));
+
+        let a_32 = Matrix::from_fn(n, n, |j, i| a[[i, j]] as f32);
+        let (l, u, p) = a_32.lup_decomp().unwrap();
+        let diff = &p * &a_32 - &l * &u;
+        assert!(diff.data().iter().all(|x| x.abs() < 1e-3));
+    }
+
+    #[test]
+    fn test_lup_decomp_in_place_slice() {
+        use matrix::BaseMatrixMut;
+
+        // A block larger than the panel width, inside a wider workspace.
+        let (n, offset) = (100, 3);
+        let mut state = 54321u32;
+        let mut workspace = Matrix::from_fn(n + offset, n + 2 * offset, |_, _| {
+            state = state.wrapping_mul(1103515245).wrapping_add(12345);
+            (state >> 16) as f64 / 65536.0 - 0.5
+        });
+        let original = workspace.clone();
+        let a = original.sub_slice([offset, offset], n, n).into_matrix();
+
+        let perm = workspace.sub_slice_mut([offset, offset], n, n).lup_decomp_in_place().unwrap();
+
+        for i in 0..n + offset {
+            for j in 0..n + 2 * offset {
+                if i < offset || j < offset || j >= n + offset {
+                    assert_eq!(workspace[[i, j]], original[[i, j]]);
+                }
+            }
+        }
+
+        let lu = workspace.sub_slice([offset, offset], n, n);
+        let l = Matrix::from_fn(n, n, |j, i| if i == j { 1.0 } else if j < i { lu[[i, j]] } else { 0.0 });
+        let u = Matrix::from_fn(n, n, |j, i| if j >= i { lu[[i, j]] } else { 0.0 });
+        let pa = a.select_rows(&perm);
+
+        let diff = pa - l * u;
+        assert!(diff.data().iter().all(|x| x.abs() < 1e-10));
+
+        let mut singular = Matrix::new(2, 2, vec![1.0, 2.0, 2.0, 4.0]);
+        assert!(singular.lup_decomp_in_place().is_err());
+    }
+
+    #[test]
+    fn test_qr_decomp_in_place_slice() {
+        use matrix::BaseMatrixMut;
+
+        let mut workspace = Matrix::new(5, 4, vec![-1.0f64, -1.0, -1.0, -1.0,
+                                                   -1.0, 4.0, 2.0, -2.0,
+                                                   -1.0, 2.0, 10.0, 2.0,
+                                                   -1.0, -2.0, 2.0, 5.0,
+                                                   -1.0, 1.0, 0.0, 3.0]);
+        let original = workspace.clone();
+        let a = original.sub_slice([1, 1], 4, 3).into_matrix();
+
+        let tau = workspace.sub_slice_mut([1, 1], 4, 3).qr_decomp_in_place();
+        assert_eq!(tau.len(), 3);
+
+        for i in 0..5 {
+            assert_eq!(workspace[[i, 0]], original[[i, 0]]);
+        }
+        assert_eq!(workspace.get_row(0), original.get_row(0));
+
+        let qr = workspace.sub_slice([1, 1], 4, 3);
+        let r = HouseholderQr::decompose(a).r();
+        for i in 0..3 {
+            for j in i..3 {
+                assert!((qr[[i, j]] - r[[i, j]]).abs() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decompositions_on_slices() {
+        use matrix::MatrixSlice;
+
+        let workspace = Matrix::new(4, 4, vec![-1.0, -1.0, -1.0, -1.0,
+                                               -1.0, 4.0, 2.0, -2.0,
+                                               -1.0, 2.0, 10.0, 2.0,
+                                               -1.0, -2.0, 2.0, 5.0]);
+        let block = MatrixSlice::from_matrix(&workspace, [1, 1], 3, 3);
+        let a = block.into_matrix();
+
+        assert_eq!(block.cholesky_cloned().unwrap(), a.cholesky().unwrap());
+        assert_eq!(block.lup_decomp_cloned().unwrap(), a.lup_decomp().unwrap());
+        assert_eq!(block.qr_decomp_cloned().unwrap(), a.clone().qr_decomp().unwrap());
+        assert_eq!(block.svd_cloned().unwrap(), a.clone().svd().unwrap());
+        assert_eq!(block.eigenvalues_cloned().unwrap(), a.eigenvalues().unwrap());
+
+        let y = Vector::new(vec![1.0, 2.0, 3.0]);
+        assert_eq!(block.solve_cloned(y.clone()).unwrap(), a.solve(y).unwrap());
+    }
+}
@@ -0,0 +1,136 @@
+//! Moore-Penrose pseudo-inverse.
+//!
+//! The pseudo-inverse is computed from the singular value decomposition,
+//! inverting only the singular values above a tolerance.
+
+use std::any::Any;
+
+use matrix::{Matrix, BaseMatrix};
+use error::Error;
+use epsilon::MachineEpsilon;
+
+use libnum::{Float, Signed};
+
+use super::numerical_rank;
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Computes the Moore-Penrose pseudo-inverse of the matrix.
+    ///
+    /// Singular values smaller than or equal to `tol` are treated as zero.
+    /// If `tol` is `None` the tolerance is `max(m, n) * eps * σ_max`, where
+    /// `eps` is the machine epsilon and `σ_max` is the largest singular value.
+    ///
+    /// For an `m` x `n` matrix the pseudo-inverse is `n` x `m`. It is equal to
+    /// the inverse for non-singular square matrices, and exists for tall, wide
+    /// and rank deficient matrices. `A⁺b` is the minimum norm least squares
+    /// solution of `Ax = b`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// // A rank one matrix.
+    /// let a = Matrix::new(2, 3, vec![1.0f64, 2.0, 2.0,
+    ///                                2.0, 4.0, 4.0]);
+    ///
+    /// let pinv = a.pseudo_inverse(None).unwrap();
+    /// assert_eq!((pinv.rows(), pinv.cols()), (3, 2));
+    ///
+    /// // A A⁺ A = A
+    /// let product = &a * &pinv * &a;
+    /// assert!(product.data().iter().zip(a.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The SVD could not be computed.
+    pub fn pseudo_inverse(&self, tol: Option<T>) -> Result<Matrix<T>, Error> {
+        let (m, n) = (self.rows, self.cols);
+        let (s, u, v) = try!(self.clone().svd());
+        let rank = numerical_rank(m, n, s.diag().into_vec(), tol);
+
+        if rank == 0 {
+            return Ok(Matrix::zeros(n, m));
+        }
+
+        // A⁺ = V Σ⁺ Uᵀ, using only the singular values above the tolerance.
+        let v_scaled = Matrix::from_fn(n, rank, |j, i| v[[i, j]] / s[[j, j]]);
+        let u_r = Matrix::from_fn(rank, m, |j, i| u[[j, i]]);
+
+        Ok(v_scaled * u_r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use matrix::decomposition::tests::assert_close;
+
+    /// Checks the four Moore-Penrose conditions.
+    fn validate_pseudo_inverse(a: &Matrix<f64>, pinv: &Matrix<f64>) {
+        assert_eq!((pinv.rows(), pinv.cols()), (a.cols(), a.rows()));
+
+        let ap = a * pinv;
+        let pa = pinv * a;
+        assert_close(&(&ap * a), a, 1e-10);
+        assert_close(&(&pa * pinv), pinv, 1e-10);
+        assert_close(&ap.transpose(), &ap, 1e-10);
+        assert_close(&pa.transpose(), &pa, 1e-10);
+    }
+
+    #[test]
+    fn test_pseudo_inverse_square() {
+        let a = Matrix::new(3, 3, vec![2.0, 0.0, 1.0, -1.0, 3.0, 0.0, 0.0, 1.0, 4.0]);
+        let pinv = a.pseudo_inverse(None).unwrap();
+
+        validate_pseudo_inverse(&a, &pinv);
+        assert_close(&pinv, &a.inverse().unwrap(), 1e-12);
+    }
+
+    #[test]
+    fn test_pseudo_inverse_tall_and_wide() {
+        let a = Matrix::new(5, 3, (0..15).map(|x| ((x * x) % 11) as f64).collect::<Vec<_>>());
+
+        let pinv = a.pseudo_inverse(None).unwrap();
+        validate_pseudo_inverse(&a, &pinv);
+
+        // For full column rank A⁺ = (AᵀA)⁻¹Aᵀ.
+        let at = a.transpose();
+        let expected = (&at * &a).inverse().unwrap() * &at;
+        assert_close(&pinv, &expected, 1e-12);
+
+        let pinv_t = at.pseudo_inverse(None).unwrap();
+        validate_pseudo_inverse(&at, &pinv_t);
+        assert_close(&pinv_t, &pinv.transpose(), 1e-12);
+    }
+
+    #[test]
+    fn test_pseudo_inverse_rank_deficient() {
+        // The third column is the sum of the first two.
+        let a = Matrix::new(4, 3, vec![1.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 2.0, 2.0, -1.0, 1.0]);
+        let pinv = a.pseudo_inverse(None).unwrap();
+
+        validate_pseudo_inverse(&a, &pinv);
+    }
+
+    #[test]
+    fn test_pseudo_inverse_tolerance() {
+        let a = Matrix::from_diag(&[4.0, 1e-3, 0.0]);
+
+        let pinv = a.pseudo_inverse(None).unwrap();
+        assert_close(&pinv, &Matrix::from_diag(&[0.25, 1e3, 0.0]), 1e-9);
+
+        // Singular values equal to the tolerance are treated as zero.
+        let pinv = a.pseudo_inverse(Some(1e-3)).unwrap();
+        assert_close(&pinv, &Matrix::from_diag(&[0.25, 0.0, 0.0]), 1e-12);
+    }
+
+    #[test]
+    fn test_pseudo_inverse_zero_matrix() {
+        let a = Matrix::<f64>::zeros(2, 3);
+        let pinv = a.pseudo_inverse(None).unwrap();
+
+        assert_eq!(pinv, Matrix::zeros(3, 2));
+    }
+}
@@ -0,0 +1,592 @@
+//! QR decomposition with compact Householder storage.
+//!
+//! Rather than forming Q explicitly, the Householder vectors are stored
+//! below the diagonal of R, and their scalar factors in a separate vector,
+//! as done in LAPACK's `geqrf`. Q can then be applied to other matrices,
+//! or formed, only when it is needed.
+//!
+//! The `FullQr` type instead stores Q explicitly, so that the decomposition
+//! can be updated with Givens rotations when rows or columns are inserted
+//! into or deleted from the matrix.
+//!
+//! References:
+//! 1. Matrix Computations, Golub and Van Loan, Sections 5.2 and 12.5.
+
+use std::any::Any;
+use std::cmp;
+
+use matrix::{Matrix, MatrixSlice, BaseMatrix, BaseMatrixMut, back_substitution};
+use vector::Vector;
+use error::{Error, ErrorKind};
+use epsilon::MachineEpsilon;
+
+use libnum::Float;
+
+use super::{has_negligible_diagonal, householder_qr_in_place, reflect_rows, rotate_cols, rotate_rows,
+            givens_rot};
+
+/// The QR decomposition of a matrix, with Q stored as a product
+/// of Householder reflections.
+///
+/// For an `m` x `n` matrix A the decomposition is A = QR, where Q is
+/// an `m` x `m` orthogonal matrix and R is an `m` x `n` upper triangular
+/// matrix.
+///
+/// # Examples
+///
+/// ```
+/// use rulinalg::matrix::{Matrix, BaseMatrix};
+/// use rulinalg::matrix::decomposition::HouseholderQr;
+///
+/// let a = Matrix::new(4, 2, vec![1.0f64, 1.0,
+///                                1.0, 2.0,
+///                                1.0, 3.0,
+///                                1.0, 4.0]);
+///
+/// let qr = HouseholderQr::decompose(a.clone());
+///
+/// let q = qr.q_thin();
+/// let r = qr.r();
+/// assert_eq!((q.rows(), q.cols()), (4, 2));
+/// assert_eq!((r.rows(), r.cols()), (2, 2));
+///
+/// let qr_product = q * r;
+/// assert!(qr_product.data().iter().zip(a.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HouseholderQr<T> {
+    // R in the upper triangle, the Householder vectors below the
+    // diagonal with their leading 1 implicit.
+    qr: Matrix<T>,
+    tau: Vec<T>,
+}
+
+impl<T: Any + Float> HouseholderQr<T> {
+    /// Computes the QR decomposition of the matrix.
+    ///
+    /// The matrix is consumed and its storage is reused for the decomposition.
+    pub fn decompose(matrix: Matrix<T>) -> HouseholderQr<T> {
+        let mut qr = matrix;
+        let tau = householder_qr_in_place(&mut qr.as_mut_slice());
+
+        HouseholderQr { qr: qr, tau: tau }
+    }
+
+    /// The upper triangular factor R.
+    ///
+    /// For an `m` x `n` matrix this is the leading `min(m, n)` x `n` block
+    /// of the full R, the remaining rows are zero.
+    pub fn r(&self) -> Matrix<T> {
+        let (p, n) = (self.tau.len(), self.qr.cols());
+        Matrix::from_fn(p, n, |j, i| if i <= j { self.qr[[i, j]] } else { T::zero() })
+    }
+
+    /// Forms the first `min(m, n)` columns of Q.
+    ///
+    /// Together with `r` this gives the thin QR decomposition.
+    pub fn q_thin(&self) -> Matrix<T> {
+        let (m, p) = (self.qr.rows(), self.tau.len());
+        let mut q = Matrix::zeros(m, p);
+        for j in 0..p {
+            q[[j, j]] = T::one();
+        }
+
+        self.apply_q(q)
+    }
+
+    /// Forms the full `m` x `m` orthogonal matrix Q.
+    pub fn q_full(&self) -> Matrix<T> {
+        self.apply_q(Matrix::identity(self.qr.rows()))
+    }
+
+    /// Computes the product Q `m` without forming Q.
+    ///
+    /// # Panics
+    ///
+    /// - The number of rows of `m` does not match the decomposed matrix.
+    pub fn apply_q(&self, mut m: Matrix<T>) -> Matrix<T> {
+        assert!(m.rows() == self.qr.rows(),
+                "Matrix must have the same number of rows as Q.");
+
+        let cols = m.cols();
+        for k in (0..self.tau.len()).rev() {
+            reflect_rows(&mut m, k, &self.reflector(k), self.tau[k], 0..cols);
+        }
+
+        m
+    }
+
+    /// Computes the product Q<sup>T</sup> `m` without forming Q.
+    ///
+    /// # Panics
+    ///
+    /// - The number of rows of `m` does not match the decomposed matrix.
+    pub fn apply_qt(&self, mut m: Matrix<T>) -> Matrix<T> {
+        assert!(m.rows() == self.qr.rows(),
+                "Matrix must have the same number of rows as Q.");
+
+        let cols = m.cols();
+        for k in 0..self.tau.len() {
+            reflect_rows(&mut m, k, &self.reflector(k), self.tau[k], 0..cols);
+        }
+
+        m
+    }
+
+    /// Computes the product Q `v` without forming Q.
+    ///
+    /// # Panics
+    ///
+    /// - The size of `v` does not match the number of rows of the decomposed matrix.
+    pub fn apply_q_vec(&self, v: Vector<T>) -> Vector<T> {
+        let size = v.size();
+        Vector::new(self.apply_q(Matrix::new(size, 1, v.into_vec())).into_vec())
+    }
+
+    /// Computes the product Q<sup>T</sup> `v` without forming Q.
+    ///
+    /// # Panics
+    ///
+    /// - The size of `v` does not match the number of rows of the decomposed matrix.
+    pub fn apply_qt_vec(&self, v: Vector<T>) -> Vector<T> {
+        let size = v.size();
+        Vector::new(self.apply_qt(Matrix::new(size, 1, v.into_vec())).into_vec())
+    }
+
+    /// Solves the linear least squares problem min ||Ax - b||.
+    ///
+    /// A is the decomposed matrix, which must have at least as many rows
+    /// as columns. When A is square this is the solution of Ax = b.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::matrix::decomposition::HouseholderQr;
+    /// use rulinalg::vector::Vector;
+    ///
+    /// // Fit a line through the points (1, 1), (2, 3), (3, 5) and (4, 7).
+    /// let a = Matrix::new(4, 2, vec![1.0f64, 1.0,
+    ///                                1.0, 2.0,
+    ///                                1.0, 3.0,
+    ///                                1.0, 4.0]);
+    /// let b = Vector::new(vec![1.0, 3.0, 5.0, 7.0]);
+    ///
+    /// let x = HouseholderQr::decompose(a).solve_least_squares(b).unwrap();
+    ///
+    /// assert!((x[0] + 1.0).abs() < 1e-12);
+    /// assert!((x[1] - 2.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The decomposed matrix has fewer rows than columns.
+    /// - The size of `b` does not match the number of rows of the decomposed matrix.
+    ///
+    /// # Failures
+    ///
+    /// - The decomposed matrix does not have full column rank.
+    pub fn solve_least_squares(&self, b: Vector<T>) -> Result<Vector<T>, Error>
+        where T: MachineEpsilon
+    {
+        let (m, n) = (self.qr.rows(), self.qr.cols());
+        assert!(m >= n,
+                "Least squares requires at least as many rows as columns.");
+
+        if has_negligible_diagonal(&self.qr, m, n) {
+            return Err(Error::new(ErrorKind::AlgebraFailure,
+                                  "Matrix does not have full column rank."));
+        }
+
+        let mut c = self.apply_qt_vec(b).into_vec();
+        c.truncate(n);
+
+        let r = MatrixSlice::from_matrix(&self.qr, [0, 0], n, n);
+        back_substitution(&r, Vector::new(c))
+    }
+
+    /// Returns the `k`th Householder vector, including its leading 1.
+    fn reflector(&self, k: usize) -> Vec<T> {
+        let mut v = Vec::with_capacity(self.qr.rows() - k);
+        v.push(T::one());
+        v.extend((k + 1..self.qr.rows()).map(|i| self.qr[[i, k]]));
+        v
+    }
+}
+
+/// The QR decomposition of a matrix, with Q and R stored explicitly.
+///
+/// For an `m` x `n` matrix A the decomposition is A = QR, where Q is
+/// an `m` x `m` orthogonal matrix and R is an `m` x `n` upper triangular
+/// matrix.
+///
+/// Rows and columns can be inserted into or deleted from A, and the
+/// decomposition updated using Givens rotations in O(m(m + n)) operations,
+/// rather than the O(mn<sup>2</sup>) needed to recompute it.
+///
+/// # Examples
+///
+/// ```
+/// use rulinalg::matrix::{Matrix, BaseMatrix};
+/// use rulinalg::matrix::decomposition::FullQr;
+/// use rulinalg::vector::Vector;
+///
+/// let a = Matrix::new(3, 2, vec![1.0f64, 2.0,
+///                                3.0, 4.0,
+///                                5.0, 6.0]);
+///
+/// let mut qr = FullQr::decompose(a);
+///
+/// // Slide the window: drop the oldest row and append a new one.
+/// qr.delete_row(0);
+/// qr.insert_row(2, Vector::new(vec![7.0, 8.0]));
+///
+/// let b = Matrix::new(3, 2, vec![3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+/// let qr_product = qr.q() * qr.r();
+/// assert!(qr_product.data().iter().zip(b.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+/// ```
+#[derive(Debug, Clone)]
+pub struct FullQr<T> {
+    q: Matrix<T>,
+    r: Matrix<T>,
+}
+
+impl<T: Any + Float> FullQr<T> {
+    /// Computes the QR decomposition of the matrix.
+    pub fn decompose(matrix: Matrix<T>) -> FullQr<T> {
+        let (m, n) = (matrix.rows(), matrix.cols());
+        let qr = HouseholderQr::decompose(matrix);
+
+        let r_thin = qr.r();
+        let mut r = Matrix::zeros(m, n);
+        for i in 0..r_thin.rows() {
+            for j in i..n {
+                r[[i, j]] = r_thin[[i, j]];
+            }
+        }
+
+        FullQr {
+            q: qr.q_full(),
+            r: r,
+        }
+    }
+
+    /// The orthogonal factor Q.
+    pub fn q(&self) -> &Matrix<T> {
+        &self.q
+    }
+
+    /// The upper triangular factor R.
+    pub fn r(&self) -> &Matrix<T> {
+        &self.r
+    }
+
+    /// Consumes the decomposition and returns the tuple (Q, R).
+    pub fn into_parts(self) -> (Matrix<T>, Matrix<T>) {
+        (self.q, self.r)
+    }
+
+    /// Updates the decomposition after inserting `row` into the matrix,
+    /// so that it becomes row `k`.
+    ///
+    /// # Panics
+    ///
+    /// - `k` is larger than the number of rows.
+    /// - The size of `row` does not match the number of columns.
+    pub fn insert_row(&mut self, k: usize, row: Vector<T>) {
+        let (m, n) = (self.r.rows(), self.r.cols());
+        assert!(k <= m, "Row index is out of bounds.");
+        assert!(row.size() == n, "Row must have the same size as the matrix rows.");
+
+        // [w; A] = diag(1, Q) [w; R], where [w; R] is upper Hessenberg.
+        let mut q = Matrix::from_fn(m + 1, m + 1, |j, i| if i == 0 || j == 0 {
+            if i == j { T::one() } else { T::zero() }
+        } else {
+            self.q[[i - 1, j - 1]]
+        });
+        let mut r = Matrix::from_fn(m + 1, n, |j, i| if i == 0 { row[j] } else { self.r[[i - 1, j]] });
+
+        for j in 0..cmp::min(m, n) {
+            let (c, s) = givens_rot(r[[j, j]], r[[j + 1, j]]);
+            rotate_rows(&mut r, j, j + 1, c, s, j..n);
+            rotate_cols(&mut q, j, j + 1, c, s, 0..m + 1);
+            r[[j + 1, j]] = T::zero();
+        }
+
+        // Move the new row of Q into place.
+        let order = (1..k + 1).chain(0..1).chain(k + 1..m + 1).collect::<Vec<_>>();
+        self.q = q.select_rows(&order);
+        self.r = r;
+    }
+
+    /// Updates the decomposition after deleting row `k` from the matrix.
+    ///
+    /// # Panics
+    ///
+    /// - `k` is not a valid row index.
+    pub fn delete_row(&mut self, k: usize) {
+        let (m, n) = (self.r.rows(), self.r.cols());
+        assert!(k < m, "Row index is out of bounds.");
+
+        // Rotate row k of Q to a multiple of the first unit vector,
+        // after which the other rows of Q are zero in the first column.
+        for j in (1..m).rev() {
+            let (c, s) = givens_rot(self.q[[k, j - 1]], self.q[[k, j]]);
+            rotate_cols(&mut self.q, j - 1, j, c, s, 0..m);
+            rotate_rows(&mut self.r, j - 1, j, c, s, j - 1..n);
+        }
+
+        let rows = (0..m).filter(|&i| i != k).collect::<Vec<_>>();
+        let cols = (1..m).collect::<Vec<_>>();
+        self.q = self.q.select(&rows, &cols);
+        self.r = self.r.select_rows(&cols);
+    }
+
+    /// Updates the decomposition after inserting `col` into the matrix,
+    /// so that it becomes column `k`.
+    ///
+    /// # Panics
+    ///
+    /// - `k` is larger than the number of columns.
+    /// - The size of `col` does not match the number of rows.
+    pub fn insert_col(&mut self, k: usize, col: Vector<T>) {
+        let (m, n) = (self.r.rows(), self.r.cols());
+        assert!(k <= n, "Column index is out of bounds.");
+        assert!(col.size() == m, "Column must have the same size as the matrix columns.");
+
+        let w = self.q.transpose() * col;
+        let mut r = Matrix::from_fn(m, n + 1, |j, i| if j < k {
+            self.r[[i, j]]
+        } else if j == k {
+            w[i]
+        } else {
+            self.r[[i, j - 1]]
+        });
+
+        // Zero the new column below the diagonal, from the bottom up.
+        for j in (k + 1..m).rev() {
+            let (c, s) = givens_rot(r[[j - 1, k]], r[[j, k]]);
+            rotate_rows(&mut r, j - 1, j, c, s, k..n + 1);
+            rotate_cols(&mut self.q, j - 1, j, c, s, 0..m);
+            r[[j, k]] = T::zero();
+        }
+
+        self.r = r;
+    }
+
+    /// Updates the decomposition after deleting column `k` from the matrix.
+    ///
+    /// # Panics
+    ///
+    /// - `k` is not a valid column index.
+    pub fn delete_col(&mut self, k: usize) {
+        let (m, n) = (self.r.rows(), self.r.cols());
+        assert!(k < n, "Column index is out of bounds.");
+
+        let cols = (0..n).filter(|&j| j != k).collect::<Vec<_>>();
+        let mut r = self.r.select_cols(&cols);
+
+        // The columns after k are now upper Hessenberg.
+        for j in k..cmp::min(n, m).saturating_sub(1) {
+            let (c, s) = givens_rot(r[[j, j]], r[[j + 1, j]]);
+            rotate_rows(&mut r, j, j + 1, c, s, j..n - 1);
+            rotate_cols(&mut self.q, j, j + 1, c, s, 0..m);
+            r[[j + 1, j]] = T::zero();
+        }
+
+        self.r = r;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use matrix::decomposition::tests::assert_close;
+    use vector::Vector;
+
+    use super::{FullQr, HouseholderQr};
+
+    fn validate_qr(a: &Matrix<f64>) {
+        let qr = HouseholderQr::decompose(a.clone());
+        let (m, n) = (a.rows(), a.cols());
+
+        let r = qr.r();
+        for i in 0..r.rows() {
+            for j in 0..i {
+                assert_eq!(r[[i, j]], 0.0);
+            }
+        }
+
+        assert_close(&(qr.q_thin() * &r), a, 1e-12);
+
+        let q = qr.q_full();
+        assert_close(&(q.transpose() * &q), &Matrix::identity(m), 1e-12);
+
+        let mut r_full = Matrix::zeros(m, n);
+        for i in 0..r.rows() {
+            for j in 0..n {
+                r_full[[i, j]] = r[[i, j]];
+            }
+        }
+        assert_close(&(&q * r_full), a, 1e-12);
+    }
+
+    #[test]
+    fn test_householder_qr_square() {
+        validate_qr(&Matrix::new(3,
+                                 3,
+                                 vec![12.0, -51.0, 4.0, 6.0, 167.0, -68.0, -4.0, 24.0, -41.0]));
+    }
+
+    #[test]
+    fn test_householder_qr_tall() {
+        validate_qr(&Matrix::new(5, 3, (0..15).map(|x| ((x * x) % 11) as f64).collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn test_householder_qr_wide() {
+        validate_qr(&Matrix::new(3, 5, (0..15).map(|x| ((x * x) % 11) as f64).collect::<Vec<_>>()));
+    }
+
+    #[test]
+    fn test_householder_qr_apply() {
+        let a = Matrix::new(4, 3, (0..12).map(|x| ((x * x) % 7) as f64).collect::<Vec<_>>());
+        let b = Matrix::new(4, 2, vec![1.0, -2.0, 3.0, 0.5, 2.0, 1.0, -1.0, 4.0]);
+        let qr = HouseholderQr::decompose(a);
+        let q = qr.q_full();
+
+        assert_close(&qr.apply_q(b.clone()), &(&q * &b), 1e-12);
+        assert_close(&qr.apply_qt(b.clone()), &(q.transpose() * &b), 1e-12);
+
+        let v = Vector::new(vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(*qr.apply_qt_vec(qr.apply_q_vec(v.clone())).data(),
+                   *qr.apply_qt(qr.apply_q(Matrix::new(4, 1, v.into_vec()))).data());
+    }
+
+    #[test]
+    fn test_householder_qr_least_squares() {
+        let a = Matrix::new(5, 2, vec![1.0f64, 0.0, 1.0, 1.0, 1.0, 2.0, 1.0, 3.0, 1.0, 4.0]);
+        let b = Vector::new(vec![1.0, 2.0, 2.0, 4.0, 5.0]);
+
+        let x = HouseholderQr::decompose(a.clone()).solve_least_squares(b.clone()).unwrap();
+
+        // The residual is orthogonal to the range of A.
+        let residual = &a * &x - b;
+        let normal = a.transpose() * residual;
+        assert!(normal.data().iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_householder_qr_least_squares_rank_deficient() {
+        let a = Matrix::new(3, 2, vec![1.0, 2.0, 2.0, 4.0, 3.0, 6.0]);
+        let b = Vector::new(vec![1.0, 2.0, 3.0]);
+
+        assert!(HouseholderQr::decompose(a).solve_least_squares(b).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_householder_qr_least_squares_wide() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+        let b = Vector::new(vec![1.0, 2.0]);
+
+        let _ = HouseholderQr::decompose(a).solve_least_squares(b);
+    }
+
+    fn validate_update(qr: &FullQr<f64>, a: &Matrix<f64>) {
+        let (m, n) = (a.rows(), a.cols());
+        let (q, r) = (qr.q(), qr.r());
+
+        assert_close(&(q.transpose() * q), &Matrix::identity(m), 1e-12);
+        for i in 0..m {
+            for j in 0..i.min(n) {
+                assert_eq!(r[[i, j]], 0.0);
+            }
+        }
+        assert_close(&(q * r), a, 1e-12);
+
+        // R is unique up to the signs of its rows.
+        let (_, r_expected) = a.clone().qr_decomp().unwrap();
+        for i in 0..m {
+            for j in 0..n {
+                assert!((r[[i, j]].abs() - r_expected[[i, j]].abs()).abs() < 1e-10);
+            }
+        }
+    }
+
+    fn update_matrix() -> Matrix<f64> {
+        Matrix::new(5, 3, (0..15).map(|x| ((x * x) % 11) as f64 - 3.0).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_full_qr_insert_row() {
+        let a = update_matrix();
+        let row = vec![1.0, -2.0, 0.5];
+
+        for k in 0..6 {
+            let mut qr = FullQr::decompose(a.clone());
+            qr.insert_row(k, Vector::new(row.clone()));
+
+            let mut data = a.data()[..3 * k].to_vec();
+            data.extend(row.iter().cloned());
+            data.extend(a.data()[3 * k..].iter().cloned());
+            validate_update(&qr, &Matrix::new(6, 3, data));
+        }
+    }
+
+    #[test]
+    fn test_full_qr_delete_row() {
+        let a = update_matrix();
+
+        for k in 0..5 {
+            let mut qr = FullQr::decompose(a.clone());
+            qr.delete_row(k);
+
+            let rows = (0..5).filter(|&i| i != k).collect::<Vec<_>>();
+            validate_update(&qr, &a.select_rows(&rows));
+        }
+    }
+
+    #[test]
+    fn test_full_qr_insert_col() {
+        let a = update_matrix();
+        let col = vec![1.0, -2.0, 0.5, 3.0, 4.0];
+
+        for k in 0..4 {
+            let mut qr = FullQr::decompose(a.clone());
+            qr.insert_col(k, Vector::new(col.clone()));
+
+            let expected = Matrix::from_fn(5, 4, |j, i| if j < k {
+                a[[i, j]]
+            } else if j == k {
+                col[i]
+            } else {
+                a[[i, j - 1]]
+            });
+            validate_update(&qr, &expected);
+        }
+    }
+
+    #[test]
+    fn test_full_qr_delete_col() {
+        let a = update_matrix();
+
+        for k in 0..3 {
+            let mut qr = FullQr::decompose(a.clone());
+            qr.delete_col(k);
+
+            let cols = (0..3).filter(|&j| j != k).collect::<Vec<_>>();
+            validate_update(&qr, &a.select_cols(&cols));
+        }
+    }
+
+    #[test]
+    fn test_full_qr_delete_col_empty_rows() {
+        let mut qr = FullQr::decompose(Matrix::<f64>::new(0, 2, vec![]));
+        qr.delete_col(0);
+
+        let (q, r) = qr.into_parts();
+        assert_eq!((q.rows(), q.cols()), (0, 0));
+        assert_eq!((r.rows(), r.cols()), (0, 1));
+    }
+}
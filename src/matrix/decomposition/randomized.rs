@@ -0,0 +1,174 @@
+//! Randomized low-rank approximations.
+//!
+//! The range of the matrix is approximated by multiplying it with a
+//! Gaussian random matrix, optionally refined with power iterations.
+//! The decomposition is then computed on the much smaller projected matrix.
+//!
+//! References:
+//! 1. [Finding structure with randomness]
+//!    (https://arxiv.org/abs/0909.4061), Halko, Martinsson and Tropp
+
+use std::any::Any;
+use std::cmp;
+
+use matrix::{Matrix, BaseMatrix};
+use error::Error;
+use epsilon::MachineEpsilon;
+
+use libnum::{Float, Signed, cast};
+use rand::Rng;
+use rand::distributions::normal::StandardNormal;
+
+use super::HouseholderQr;
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Truncated Singular Value Decomposition
+    ///
+    /// Computes an approximation of the `k` largest singular values and their
+    /// singular vectors using a randomized range finder.
+    ///
+    /// Returns Σ, U, V, such that `self` ≈ U Σ V<sup>T</sup>. Σ is a `k` x `k` diagonal
+    /// matrix of singular values in non-increasing order, U and V have `k` orthonormal
+    /// columns.
+    ///
+    /// The range is sampled with `k + oversampling` random vectors, and refined with
+    /// `power_iters` power iterations. Both improve the accuracy when the singular values
+    /// decay slowly, typical values are an oversampling of 5-10 and 1-2 power iterations.
+    ///
+    /// The random matrix is drawn from `rng`, so results are reproducible with a seeded
+    /// generator. This method requires the `rand` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// extern crate rand;
+    /// extern crate rulinalg;
+    ///
+    /// # fn main() {
+    /// use rand::{SeedableRng, StdRng};
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(4, 3, vec![2.0f64, 0.0, 0.0,
+    ///                                0.0, 0.0, 0.0,
+    ///                                0.0, 5.0, 0.0,
+    ///                                0.0, 0.0, 0.1]);
+    ///
+    /// let mut rng = StdRng::from_seed(&[1, 2, 3, 4]);
+    /// let (s, u, v) = a.truncated_svd(1, 2, 1, &mut rng).unwrap();
+    ///
+    /// assert!((s[[0, 0]] - 5.0).abs() < 1e-12);
+    /// assert!((u[[2, 0]].abs() - 1.0).abs() < 1e-12);
+    /// assert!((v[[1, 0]].abs() - 1.0).abs() < 1e-12);
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - `k` is zero or larger than the number of rows or columns.
+    ///
+    /// # Failures
+    ///
+    /// - The SVD of the projected matrix could not be computed.
+    pub fn truncated_svd<R: Rng>(&self,
+                                 k: usize,
+                                 oversampling: usize,
+                                 power_iters: usize,
+                                 rng: &mut R)
+                                 -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error> {
+        let (m, n) = (self.rows, self.cols);
+        assert!(k > 0 && k <= cmp::min(m, n),
+                "Number of singular values must be between 1 and the smallest dimension.");
+
+        let l = cmp::min(k + oversampling, cmp::min(m, n));
+
+        // Sample the range of the matrix with a Gaussian sketch.
+        let omega = Matrix::new(n,
+                                l,
+                                (0..n * l)
+                                    .map(|_| {
+                                        let StandardNormal(x) = rng.gen::<StandardNormal>();
+                                        cast::<f64, T>(x).unwrap()
+                                    })
+                                    .collect::<Vec<_>>());
+        let mut q = HouseholderQr::decompose(self * omega).q_thin();
+
+        // Power iterations, orthonormalizing at each step to preserve the small singular values.
+        if power_iters > 0 {
+            let self_t = self.transpose();
+            for _ in 0..power_iters {
+                let z = HouseholderQr::decompose(&self_t * &q).q_thin();
+                q = HouseholderQr::decompose(self * z).q_thin();
+            }
+        }
+
+        let b = q.transpose() * self;
+        let (s, u_b, v) = try!(b.svd());
+
+        let u = q * u_b;
+        let indices = (0..k).collect::<Vec<_>>();
+
+        Ok((s.select(&indices, &indices), u.select_cols(&indices), v.select_cols(&indices)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use rand::{Rng, SeedableRng, StdRng};
+
+    use super::super::HouseholderQr;
+
+    fn random_orthonormal(rows: usize, cols: usize, rng: &mut StdRng) -> Matrix<f64> {
+        let data = rng.gen_iter::<f64>().take(rows * cols).map(|x| x - 0.5).collect::<Vec<_>>();
+        HouseholderQr::decompose(Matrix::new(rows, cols, data)).q_thin()
+    }
+
+    #[test]
+    fn test_truncated_svd() {
+        let mut rng = StdRng::from_seed(&[12, 2049, 4000, 33]);
+
+        // A 60 x 40 matrix with quickly decaying singular values
+        let values = (0..20).map(|i| 10.0 * 0.5f64.powi(i)).collect::<Vec<_>>();
+        let u = random_orthonormal(60, 20, &mut rng);
+        let v = random_orthonormal(40, 20, &mut rng);
+        let a = &u * Matrix::from_diag(&values) * v.transpose();
+
+        let (s, u_k, v_k) = a.truncated_svd(3, 10, 2, &mut rng).unwrap();
+
+        assert_eq!((s.rows(), s.cols()), (3, 3));
+        assert_eq!((u_k.rows(), u_k.cols()), (60, 3));
+        assert_eq!((v_k.rows(), v_k.cols()), (40, 3));
+
+        for i in 0..3 {
+            assert!((s[[i, i]] - values[i]).abs() < 1e-10);
+        }
+
+        // A v = σ u for each singular triplet
+        let av = &a * &v_k;
+        let us = &u_k * &s;
+        assert!(av.data().iter().zip(us.data().iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_truncated_svd_reproducible() {
+        let a = Matrix::new(5, 4, (0..20).map(|x| (x * x % 7) as f64).collect::<Vec<_>>());
+
+        let mut rng = StdRng::from_seed(&[1, 2, 3, 4]);
+        let (s1, u1, v1) = a.truncated_svd(2, 1, 0, &mut rng).unwrap();
+        let mut rng = StdRng::from_seed(&[1, 2, 3, 4]);
+        let (s2, u2, v2) = a.truncated_svd(2, 1, 0, &mut rng).unwrap();
+
+        assert_eq!(s1, s2);
+        assert_eq!(u1, u2);
+        assert_eq!(v1, v2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_truncated_svd_too_many_values() {
+        let a = Matrix::new(3, 2, vec![1.0f64; 6]);
+        let mut rng = StdRng::from_seed(&[1, 2, 3, 4]);
+
+        let _ = a.truncated_svd(3, 0, 0, &mut rng);
+    }
+}
@@ -0,0 +1,1018 @@
+//! Real Schur form and complex eigenvalues.
+//!
+//! The Francis double-shift QR iteration reduces an upper Hessenberg matrix
+//! to quasi-upper-triangular form - an upper triangular matrix with 1x1 and 2x2
+//! blocks on the diagonal. The 1x1 blocks hold the real eigenvalues and the 2x2
+//! blocks hold the complex conjugate pairs. Eigenvectors are computed by
+//! back-substitution on the quasi-triangular matrix.
+//!
+//! References:
+//! 1. Matrix Computations, Golub and Van Loan, Section 7.5.
+
+use std::any::Any;
+use std::cmp;
+
+use matrix::{Matrix, BaseMatrix};
+use error::{Error, ErrorKind};
+use epsilon::MachineEpsilon;
+use utils;
+
+use libnum::{Float, Signed, cast};
+use num_complex::Complex;
+
+use super::{hessenberg_reduction, householder_vector, reflect_rows, reflect_cols, rotate_rows,
+            rotate_cols, givens_rot};
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Complex eigenvalues of a square matrix.
+    ///
+    /// Returns a Vec of (possibly complex) eigenvalues. Complex eigenvalues
+    /// come in conjugate pairs, with the eigenvalue with positive imaginary
+    /// part first.
+    ///
+    /// The eigenvalues are read from the real Schur form produced by the
+    /// Francis double-shift QR iteration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// // A rotation by 90 degrees.
+    /// let a = Matrix::new(2, 2, vec![0.0f64, -1.0, 1.0, 0.0]);
+    /// let e = a.complex_eigenvalues().expect("We should be able to compute these eigenvalues!");
+    ///
+    /// assert!(e[0].re.abs() < 1e-14 && (e[0].im - 1.0).abs() < 1e-14);
+    /// assert!(e[1].re.abs() < 1e-14 && (e[1].im + 1.0).abs() < 1e-14);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The Francis iteration fails to converge.
+    pub fn complex_eigenvalues(&self) -> Result<Vec<Complex<T>>, Error> {
+        let n = self.rows();
+        assert!(n == self.cols,
+                "Matrix must be square for eigenvalue computation.");
+
+        let mut h = self.clone();
+        hessenberg_reduction(&mut h, None);
+        try!(francis_double_shift(&mut h, None));
+
+        Ok(quasi_triangular_eigenvalues(&h))
+    }
+
+    /// Real Schur decomposition of a square matrix.
+    ///
+    /// Returns the tuple (Q, T) where Q is orthogonal, T is quasi-upper-triangular
+    /// and `A = Q T Q^T`. The diagonal of T is made up of 1x1 blocks holding the real
+    /// eigenvalues and 2x2 blocks holding the complex conjugate pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 3, vec![1.0f64, 2.0, 0.0, -2.0, 1.0, 3.0, 0.0, 1.0, 4.0]);
+    /// let (q, t) = a.clone().schur().expect("We should be able to compute the Schur form!");
+    ///
+    /// // Q T Q^T reconstructs the original matrix
+    /// let b = &q * &t * q.transpose();
+    /// assert!(a.data().iter().zip(b.data().iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The Francis iteration fails to converge.
+    pub fn schur(self) -> Result<(Matrix<T>, Matrix<T>), Error> {
+        let n = self.rows();
+        assert!(n == self.cols, "Matrix must be square for Schur decomposition.");
+
+        let mut t = self;
+        let mut q = Matrix::identity(n);
+        hessenberg_reduction(&mut t, Some(&mut q));
+        try!(francis_double_shift(&mut t, Some(&mut q)));
+
+        Ok((q, t))
+    }
+
+    /// Reorders a real Schur decomposition so that the selected
+    /// eigenvalues appear in the top-left of the quasi-triangular matrix.
+    ///
+    /// `self` must be the quasi-upper-triangular matrix T and `q` the orthogonal
+    /// matrix from `schur`. Both are updated in place so that `Q T Q^T` is preserved,
+    /// the relative order of the selected blocks is kept.
+    ///
+    /// The `select` function is called once for each diagonal block. Complex
+    /// conjugate pairs are selected together, using the eigenvalue with positive
+    /// imaginary part.
+    ///
+    /// Returns the number of selected eigenvalues, the leading columns of `q`
+    /// up to this number span the corresponding invariant subspace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 3, vec![3.0f64, 1.0, 0.0, 0.0, -1.0, 2.0, 0.0, 0.0, -2.0]);
+    /// let (mut q, mut t) = a.schur().unwrap();
+    ///
+    /// // Move the stable eigenvalues to the top.
+    /// let k = t.reorder_schur(&mut q, |e| e.re < 0.0).unwrap();
+    ///
+    /// assert_eq!(k, 2);
+    /// assert!(t[[0, 0]] < 0.0 && t[[1, 1]] < 0.0);
+    /// assert!((t[[2, 2]] - 3.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    /// - `q` does not have the same dimensions as the matrix.
+    ///
+    /// # Failures
+    ///
+    /// - Two blocks could not be swapped stably, this may occur if
+    ///   their eigenvalues are very close.
+    pub fn reorder_schur<F>(&mut self, q: &mut Matrix<T>, mut select: F) -> Result<usize, Error>
+        where F: FnMut(Complex<T>) -> bool
+    {
+        let n = self.rows();
+        assert!(n == self.cols, "Matrix must be square to reorder Schur form.");
+        assert!(q.rows() == n && q.cols() == n,
+                "Orthogonal matrix must have the same dimensions as the Schur form.");
+
+        // The number of selected eigenvalues moved to the top so far
+        let mut selected = 0;
+
+        let mut i = 0;
+        while i < n {
+            let size = if i + 1 < n && self[[i + 1, i]] != T::zero() { 2 } else { 1 };
+            let eigenvalue = if size == 2 {
+                let (re, im) = complex_block_eigenvalue(self[[i, i]],
+                                                        self[[i, i + 1]],
+                                                        self[[i + 1, i]],
+                                                        self[[i + 1, i + 1]]);
+                Complex::new(re, im)
+            } else {
+                Complex::new(self[[i, i]], T::zero())
+            };
+
+            if select(eigenvalue) {
+                // Swap the block upwards until it sits below the previously selected blocks.
+                let mut k = i;
+                while k > selected {
+                    let prev_size = if k >= 2 && self[[k - 1, k - 2]] != T::zero() {
+                        2
+                    } else {
+                        1
+                    };
+
+                    try!(swap_schur_blocks(self, q, k - prev_size, prev_size, size));
+                    k -= prev_size;
+                }
+
+                selected += size;
+            }
+
+            i += size;
+        }
+
+        Ok(selected)
+    }
+
+    /// Eigendecomposition of a general square matrix.
+    ///
+    /// Returns a Vec of (possibly complex) eigenvalues, ordered as in
+    /// `complex_eigenvalues`, and a complex matrix with the corresponding
+    /// right eigenvectors as the columns. The eigenvectors are normalized
+    /// to unit Euclidean norm.
+    ///
+    /// The eigenvectors are computed by back-substitution on the real Schur
+    /// form. Eigenvectors for complex conjugate eigenvalues are themselves
+    /// complex conjugates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(2, 2, vec![0.0f64, -1.0, 1.0, 0.0]);
+    /// let (e, v) = a.complex_eigendecomp().expect("We should be able to compute this eigendecomp!");
+    ///
+    /// // A v = λ v for the first eigenpair
+    /// let av0 = v[[0, 0]] * a[[0, 0]] + v[[1, 0]] * a[[0, 1]];
+    /// assert!((av0 - e[0] * v[[0, 0]]).norm() < 1e-14);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The Francis iteration fails to converge.
+    pub fn complex_eigendecomp(&self) -> Result<(Vec<Complex<T>>, Matrix<Complex<T>>), Error> {
+        let (q, t) = try!(self.clone().schur());
+
+        Ok((quasi_triangular_eigenvalues(&t), schur_eigenvectors(&t, &q, false)))
+    }
+
+    /// Left eigendecomposition of a general square matrix.
+    ///
+    /// Returns a Vec of (possibly complex) eigenvalues, ordered as in
+    /// `complex_eigenvalues`, and a complex matrix with the corresponding
+    /// left eigenvectors as the columns. Each left eigenvector `u` satisfies
+    /// `u^H A = λ u^H`, and is normalized to unit Euclidean norm.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// // The transition matrix of a Markov chain
+    /// let p = Matrix::new(2, 2, vec![0.9f64, 0.1, 0.5, 0.5]);
+    /// let (e, u) = p.complex_left_eigendecomp().unwrap();
+    ///
+    /// // The stationary distribution is the left eigenvector for eigenvalue 1.
+    /// let k = if (e[0].re - 1.0).abs() < 1e-12 { 0 } else { 1 };
+    /// let ratio = u[[0, k]] / u[[1, k]];
+    /// assert!((ratio.re - 5.0).abs() < 1e-12 && ratio.im.abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The Francis iteration fails to converge.
+    pub fn complex_left_eigendecomp(&self) -> Result<(Vec<Complex<T>>, Matrix<Complex<T>>), Error> {
+        let (q, t) = try!(self.clone().schur());
+
+        Ok((quasi_triangular_eigenvalues(&t), schur_eigenvectors(&t, &q, true)))
+    }
+}
+
+/// Reduces the upper Hessenberg matrix `h` to real Schur form in place
+/// using the Francis double-shift QR iteration.
+///
+/// Deflation is checked across the whole active block so that small subdiagonal
+/// entries anywhere split the problem. On completion every 2x2 block on the diagonal
+/// corresponds to a pair of complex conjugate eigenvalues.
+///
+/// If `q` is given the orthogonal transformations are accumulated into it
+/// from the right.
+fn francis_double_shift<T>(h: &mut Matrix<T>, mut q: Option<&mut Matrix<T>>) -> Result<(), Error>
+    where T: Any + Float + MachineEpsilon
+{
+    let n = h.rows();
+    if n < 2 {
+        return Ok(());
+    }
+
+    let eps = <T as MachineEpsilon>::epsilon();
+    let h_norm = utils::dot(&h.data, &h.data).sqrt();
+    let max_iterations = 30 * cmp::max(n, 10);
+    let mut total_iterations = 0;
+    let mut iterations = 0;
+
+    // The final index of the active block
+    let mut hi = n - 1;
+
+    while hi > 0 {
+        // Find the start of the active block by looking for a negligible subdiagonal
+        let mut lo = hi;
+        while lo > 0 {
+            let mut s = h[[lo - 1, lo - 1]].abs() + h[[lo, lo]].abs();
+            if s == T::zero() {
+                s = h_norm;
+            }
+
+            if h[[lo, lo - 1]].abs() <= eps * s {
+                h[[lo, lo - 1]] = T::zero();
+                break;
+            }
+            lo -= 1;
+        }
+
+        if lo == hi {
+            // A 1x1 block has converged.
+            hi -= 1;
+            iterations = 0;
+            continue;
+        }
+
+        if lo + 1 == hi {
+            // A 2x2 block has converged.
+            standardize_block(h, &mut q, lo);
+
+            if lo < 2 {
+                break;
+            }
+            hi = lo - 1;
+            iterations = 0;
+            continue;
+        }
+
+        iterations += 1;
+        total_iterations += 1;
+        if total_iterations > max_iterations {
+            return Err(Error::new(ErrorKind::DecompFailure,
+                                  "Francis iteration did not converge."));
+        }
+
+        // The shifts are the eigenvalues of the trailing 2x2 block, given by
+        // their sum and product. Use an exceptional shift if convergence is slow.
+        let (s, t) = if iterations % 10 == 0 {
+            let w = h[[hi, hi - 1]].abs() + h[[hi - 1, hi - 2]].abs();
+            (cast::<f64, T>(1.5).unwrap() * w, w * w)
+        } else {
+            (h[[hi - 1, hi - 1]] + h[[hi, hi]],
+             h[[hi - 1, hi - 1]] * h[[hi, hi]] - h[[hi - 1, hi]] * h[[hi, hi - 1]])
+        };
+
+        // The first column of (H - aI)(H - bI) for the active block.
+        let mut x = h[[lo, lo]] * h[[lo, lo]] + h[[lo, lo + 1]] * h[[lo + 1, lo]] -
+                    s * h[[lo, lo]] + t;
+        let mut y = h[[lo + 1, lo]] * (h[[lo, lo]] + h[[lo + 1, lo + 1]] - s);
+        let mut z = h[[lo + 1, lo]] * h[[lo + 2, lo + 1]];
+
+        // Chase the bulge down the active block
+        for k in lo..hi - 1 {
+            let (v, tau, _) = householder_vector(&[x, y, z]);
+            let r = if k > lo { k - 1 } else { lo };
+
+            reflect_rows(h, k, &v, tau, r..n);
+            reflect_cols(h, k, &v, tau, 0..cmp::min(k + 4, hi + 1));
+            if let Some(ref mut q) = q {
+                reflect_cols(q, k, &v, tau, 0..n);
+            }
+
+            if k > lo {
+                h[[k + 1, k - 1]] = T::zero();
+                h[[k + 2, k - 1]] = T::zero();
+            }
+
+            x = h[[k + 1, k]];
+            y = h[[k + 2, k]];
+
+            if k + 2 < hi {
+                z = h[[k + 3, k]];
+            }
+        }
+
+        let (c, s) = givens_rot(x, y);
+        rotate_rows(h, hi - 1, hi, c, s, hi - 2..n);
+        rotate_cols(h, hi - 1, hi, c, s, 0..hi + 1);
+        if let Some(ref mut q) = q {
+            rotate_cols(q, hi - 1, hi, c, s, 0..n);
+        }
+        h[[hi, hi - 2]] = T::zero();
+    }
+
+    Ok(())
+}
+
+/// Standardizes the converged 2x2 diagonal block of `h` starting at `k`.
+///
+/// If the block has real eigenvalues it is rotated to upper triangular
+/// form, otherwise it is left as is.
+fn standardize_block<T>(h: &mut Matrix<T>, q: &mut Option<&mut Matrix<T>>, k: usize)
+    where T: Any + Float
+{
+    let n = h.rows();
+    let (a, b, c, d) = (h[[k, k]], h[[k, k + 1]], h[[k + 1, k]], h[[k + 1, k + 1]]);
+
+    if c == T::zero() {
+        return;
+    }
+
+    let p = (a - d) / (T::one() + T::one());
+    let discr = p * p + b * c;
+
+    if discr < T::zero() {
+        return;
+    }
+
+    // (z, c) is an eigenvector for the eigenvalue d + z.
+    let z = if p >= T::zero() {
+        p + discr.sqrt()
+    } else {
+        p - discr.sqrt()
+    };
+    let (cos, sin) = givens_rot(z, c);
+
+    rotate_rows(h, k, k + 1, cos, sin, k..n);
+    rotate_cols(h, k, k + 1, cos, sin, 0..k + 2);
+    if let Some(ref mut q) = *q {
+        rotate_cols(q, k, k + 1, cos, sin, 0..n);
+    }
+    h[[k + 1, k]] = T::zero();
+}
+
+/// Swaps the adjacent diagonal blocks of sizes `p` and `r` starting at index `k`
+/// of the quasi-triangular matrix `t`, accumulating the transformation into `q`.
+///
+/// The Sylvester equation `A11 X - X A22 = A12` is solved for the off-diagonal block,
+/// so that `[-X; I]` spans the invariant subspace of the lower block. An orthogonal
+/// basis of this subspace is then moved to the front.
+fn swap_schur_blocks<T>(t: &mut Matrix<T>, q: &mut Matrix<T>, k: usize, p: usize, r: usize)
+                        -> Result<(), Error>
+    where T: Any + Float + MachineEpsilon
+{
+    let n = t.rows();
+    let m = p + r;
+    let eps = <T as MachineEpsilon>::epsilon();
+
+    let mut block = t.select(&(k..k + m).collect::<Vec<_>>(),
+                             &(k..k + m).collect::<Vec<_>>());
+    let block_norm = utils::dot(&block.data, &block.data).sqrt();
+
+    // Solve the Sylvester equation using its Kronecker product form.
+    let size = p * r;
+    let mut coeffs = Matrix::zeros(size, size);
+    let mut rhs = vec![T::zero(); size];
+    for i in 0..p {
+        for j in 0..r {
+            let row = i * r + j;
+            rhs[row] = block[[i, p + j]];
+
+            for l in 0..p {
+                coeffs[[row, l * r + j]] = coeffs[[row, l * r + j]] + block[[i, l]];
+            }
+            for l in 0..r {
+                coeffs[[row, i * r + l]] = coeffs[[row, i * r + l]] - block[[p + l, p + j]];
+            }
+        }
+    }
+
+    let mut small = eps * block_norm;
+    if small == T::zero() {
+        small = T::min_positive_value();
+    }
+    let x = solve_small_system(coeffs, rhs, small);
+
+    let mut basis = Matrix::zeros(m, r);
+    for i in 0..p {
+        for j in 0..r {
+            basis[[i, j]] = -x[i * r + j];
+        }
+    }
+    for j in 0..r {
+        basis[[p + j, j]] = T::one();
+    }
+
+    // Compute the reflectors and check the swap on the block before touching `t`.
+    let mut reflectors = Vec::with_capacity(r);
+    for j in 0..r {
+        let column = (j..m).map(|i| basis[[i, j]]).collect::<Vec<_>>();
+        let (v, tau, _) = householder_vector(&column);
+
+        reflect_rows(&mut basis, j, &v, tau, j..r);
+        reflect_rows(&mut block, j, &v, tau, 0..m);
+        reflect_cols(&mut block, j, &v, tau, 0..m);
+        reflectors.push((v, tau));
+    }
+
+    let mut residual = T::zero();
+    for i in r..m {
+        for j in 0..r {
+            residual = residual.max(block[[i, j]].abs());
+        }
+    }
+
+    if residual > cast::<f64, T>(10.0).unwrap() * eps * block_norm {
+        return Err(Error::new(ErrorKind::DecompFailure,
+                              "Could not stably swap the blocks of the Schur form."));
+    }
+
+    for (j, &(ref v, tau)) in reflectors.iter().enumerate() {
+        reflect_rows(t, k + j, v, tau, k..n);
+        reflect_cols(t, k + j, v, tau, 0..k + m);
+        reflect_cols(q, k + j, v, tau, 0..n);
+    }
+
+    for i in k + r..k + m {
+        for j in k..k + r {
+            t[[i, j]] = T::zero();
+        }
+    }
+
+    if r == 2 {
+        standardize_block(t, &mut Some(&mut *q), k);
+    }
+    if p == 2 {
+        standardize_block(t, &mut Some(&mut *q), k + r);
+    }
+
+    Ok(())
+}
+
+/// Solves the small linear system `a x = b` using Gaussian elimination
+/// with complete pivoting.
+///
+/// Pivots smaller than `small` are replaced by `small`, so that a nearly
+/// singular system still gives a (large) solution.
+fn solve_small_system<T: Float>(mut a: Matrix<T>, mut b: Vec<T>, small: T) -> Vec<T> {
+    let n = b.len();
+    let mut col_perm = (0..n).collect::<Vec<_>>();
+
+    for k in 0..n {
+        // Find the largest remaining entry and move it to the pivot position.
+        let (mut pivot_row, mut pivot_col) = (k, k);
+        for i in k..n {
+            for j in k..n {
+                if a[[i, j]].abs() > a[[pivot_row, pivot_col]].abs() {
+                    pivot_row = i;
+                    pivot_col = j;
+                }
+            }
+        }
+
+        if pivot_row != k {
+            for j in 0..n {
+                let tmp = a[[k, j]];
+                a[[k, j]] = a[[pivot_row, j]];
+                a[[pivot_row, j]] = tmp;
+            }
+            b.swap(k, pivot_row);
+        }
+        if pivot_col != k {
+            for i in 0..n {
+                let tmp = a[[i, k]];
+                a[[i, k]] = a[[i, pivot_col]];
+                a[[i, pivot_col]] = tmp;
+            }
+            col_perm.swap(k, pivot_col);
+        }
+
+        if a[[k, k]].abs() < small {
+            a[[k, k]] = small;
+        }
+
+        for i in k + 1..n {
+            let factor = a[[i, k]] / a[[k, k]];
+            for j in k..n {
+                a[[i, j]] = a[[i, j]] - factor * a[[k, j]];
+            }
+            b[i] = b[i] - factor * b[k];
+        }
+    }
+
+    let mut y = vec![T::zero(); n];
+    for i in (0..n).rev() {
+        let mut sum = b[i];
+        for j in i + 1..n {
+            sum = sum - a[[i, j]] * y[j];
+        }
+        y[i] = sum / a[[i, i]];
+    }
+
+    let mut x = vec![T::zero(); n];
+    for (i, &j) in col_perm.iter().enumerate() {
+        x[j] = y[i];
+    }
+    x
+}
+
+/// Computes the eigenvectors of `A = Q T Q^T` from its real Schur form.
+///
+/// The eigenvectors are returned as the columns of a complex matrix, in the
+/// same order as the eigenvalues from `quasi_triangular_eigenvalues`. If `left`
+/// is true the left eigenvectors are computed instead.
+fn schur_eigenvectors<T>(t: &Matrix<T>, q: &Matrix<T>, left: bool) -> Matrix<Complex<T>>
+    where T: Any + Float + MachineEpsilon
+{
+    let n = t.rows();
+
+    // The left eigenvectors of T are found from the right eigenvectors of T^T,
+    // whose rows and columns are reversed to keep it quasi-upper-triangular.
+    let t_rev = if left {
+        Some(Matrix::new(n, n, (0..n * n).map(|x| t[[n - 1 - x % n, n - 1 - x / n]]).collect::<Vec<_>>()))
+    } else {
+        None
+    };
+
+    let mut eigenvectors = vec![Complex::new(T::zero(), T::zero()); n * n];
+
+    let mut k = 0;
+    while k < n {
+        let size = if k + 1 < n && t[[k + 1, k]] != T::zero() { 2 } else { 1 };
+        let eigenvalue = if size == 2 {
+            let (re, im) = complex_block_eigenvalue(t[[k, k]],
+                                                    t[[k, k + 1]],
+                                                    t[[k + 1, k]],
+                                                    t[[k + 1, k + 1]]);
+            Complex::new(re, im)
+        } else {
+            Complex::new(t[[k, k]], T::zero())
+        };
+
+        let x = match t_rev {
+            Some(ref t_rev) => {
+                let mut x = quasi_triangular_eigenvector(t_rev, n - k - size, eigenvalue);
+                x.reverse();
+                x
+            }
+            None => quasi_triangular_eigenvector(t, k, eigenvalue),
+        };
+
+        // Transform back with Q and normalize.
+        let mut v = (0..n)
+            .map(|i| {
+                x.iter()
+                    .enumerate()
+                    .fold(Complex::new(T::zero(), T::zero()),
+                          |acc, (j, x_j)| acc + x_j.scale(q[[i, j]]))
+            })
+            .collect::<Vec<_>>();
+
+        if left {
+            for v_i in &mut v {
+                *v_i = v_i.conj();
+            }
+        }
+
+        let norm = v.iter().fold(T::zero(), |acc, v_i| acc + v_i.norm_sqr()).sqrt();
+        for (i, v_i) in v.iter().enumerate() {
+            let v_i = v_i.unscale(norm);
+            eigenvectors[i * n + k] = v_i;
+            if size == 2 {
+                eigenvectors[i * n + k + 1] = v_i.conj();
+            }
+        }
+
+        k += size;
+    }
+
+    Matrix::new(n, n, eigenvectors)
+}
+
+/// Computes an eigenvector of the quasi-upper-triangular matrix `t` for the
+/// eigenvalue of the diagonal block starting at `k`, using back-substitution.
+///
+/// Nearly singular diagonal blocks are perturbed slightly so that
+/// repeated eigenvalues still give a (possibly inaccurate) eigenvector.
+fn quasi_triangular_eigenvector<T>(t: &Matrix<T>, k: usize, eigenvalue: Complex<T>) -> Vec<Complex<T>>
+    where T: Any + Float + MachineEpsilon
+{
+    let n = t.rows();
+    let eps = <T as MachineEpsilon>::epsilon();
+    let zero = Complex::new(T::zero(), T::zero());
+
+    let t_norm = utils::dot(&t.data, &t.data).sqrt();
+    let mut small = eps * t_norm;
+    if small == T::zero() {
+        small = T::min_positive_value();
+    }
+    let big = T::one() / eps;
+
+    let mut x = vec![zero; n];
+    let end = if k + 1 < n && t[[k + 1, k]] != T::zero() {
+        // An eigenvector of the 2x2 block [a b; c d] is (b, λ - a).
+        x[k] = Complex::new(t[[k, k + 1]], T::zero());
+        x[k + 1] = eigenvalue - Complex::new(t[[k, k]], T::zero());
+        k + 2
+    } else {
+        x[k] = Complex::new(T::one(), T::zero());
+        k + 1
+    };
+
+    let mut i = k;
+    while i > 0 {
+        let start = if i >= 2 && t[[i - 1, i - 2]] != T::zero() { i - 2 } else { i - 1 };
+
+        let rhs = (start..i)
+            .map(|r| {
+                (i..end).fold(zero, |acc, j| acc - x[j].scale(t[[r, j]]))
+            })
+            .collect::<Vec<_>>();
+
+        if i - start == 1 {
+            let d = Complex::new(t[[start, start]], T::zero()) - eigenvalue;
+            x[start] = if d.norm() < small {
+                rhs[0].unscale(small)
+            } else {
+                rhs[0] / d
+            };
+        } else {
+            // Solve the 2x2 system with Cramer's rule.
+            let a = Complex::new(t[[start, start]], T::zero()) - eigenvalue;
+            let b = Complex::new(t[[start, start + 1]], T::zero());
+            let c = Complex::new(t[[start + 1, start]], T::zero());
+            let d = Complex::new(t[[start + 1, start + 1]], T::zero()) - eigenvalue;
+
+            let small_det = (small * t_norm).max(T::min_positive_value());
+            let det = a * d - b * c;
+            let (x0, x1) = (rhs[0] * d - b * rhs[1], a * rhs[1] - c * rhs[0]);
+            if det.norm() < small_det {
+                x[start] = x0.unscale(small_det);
+                x[start + 1] = x1.unscale(small_det);
+            } else {
+                x[start] = x0 / det;
+                x[start + 1] = x1 / det;
+            }
+        }
+
+        // Rescale to avoid overflow during the substitution.
+        let max = x[start..end].iter().fold(T::zero(), |acc, x_j| acc.max(x_j.norm()));
+        if max > big {
+            for x_j in &mut x[start..end] {
+                *x_j = x_j.unscale(max);
+            }
+        }
+
+        i = start;
+    }
+
+    x
+}
+
+/// Reads the eigenvalues from the diagonal blocks of a matrix in real Schur form.
+fn quasi_triangular_eigenvalues<T: Float>(t: &Matrix<T>) -> Vec<Complex<T>> {
+    let n = t.rows();
+    let mut eigenvalues = Vec::with_capacity(n);
+
+    let mut i = 0;
+    while i < n {
+        if i + 1 < n && t[[i + 1, i]] != T::zero() {
+            let (re, im) = complex_block_eigenvalue(t[[i, i]],
+                                                    t[[i, i + 1]],
+                                                    t[[i + 1, i]],
+                                                    t[[i + 1, i + 1]]);
+            eigenvalues.push(Complex::new(re, im));
+            eigenvalues.push(Complex::new(re, -im));
+            i += 2;
+        } else {
+            eigenvalues.push(Complex::new(t[[i, i]], T::zero()));
+            i += 1;
+        }
+    }
+
+    eigenvalues
+}
+
+/// Returns the real and (non-negative) imaginary parts of an eigenvalue
+/// of the 2x2 block `[a b; c d]` which has complex eigenvalues.
+fn complex_block_eigenvalue<T: Float>(a: T, b: T, c: T, d: T) -> (T, T) {
+    let two = T::one() + T::one();
+    let p = (a - d) / two;
+    let discr = p * p + b * c;
+
+    ((a + d) / two, (-discr).max(T::zero()).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+    use num_complex::Complex;
+
+    fn assert_eigenvalues(actual: &[(f64, f64)], expected: &[(f64, f64)], tol: f64) {
+        assert_eq!(actual.len(), expected.len());
+
+        for &(re, im) in expected {
+            assert!(actual.iter().any(|&(x, y)| (x - re).abs() < tol && (y - im).abs() < tol),
+                    format!("eigenvalue {} + {}i not found in {:?}", re, im, actual));
+        }
+    }
+
+    #[test]
+    fn test_2_by_2_complex_eigenvalues() {
+        let a = Matrix::new(2, 2, vec![1.0f64, -3.0, 1.0, 1.0]);
+        // characteristic polynomial is λ² − 2λ + 4 = 0
+        let eigs = a.complex_eigenvalues().unwrap();
+
+        assert_eq!(eigs.len(), 2);
+        assert!((eigs[0].re - 1.0).abs() < 1e-14);
+        assert!((eigs[0].im - 3f64.sqrt()).abs() < 1e-14);
+        assert!((eigs[1].re - 1.0).abs() < 1e-14);
+        assert!((eigs[1].im + 3f64.sqrt()).abs() < 1e-14);
+    }
+
+    #[test]
+    fn test_companion_matrix_complex_eigenvalues() {
+        // Companion matrix of x⁴ - 1, with roots 1, -1, i and -i.
+        let a = Matrix::new(4,
+                            4,
+                            vec![0.0f64, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0,
+                                 0.0, 0.0, 1.0, 0.0]);
+        let eigs = a.complex_eigenvalues()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.re, e.im))
+            .collect::<Vec<_>>();
+
+        assert_eigenvalues(&eigs,
+                           &[(1.0, 0.0), (-1.0, 0.0), (0.0, 1.0), (0.0, -1.0)],
+                           1e-10);
+    }
+
+    #[test]
+    fn test_rotation_complex_eigenvalues() {
+        // A rotation about the z axis followed by a scaling along it.
+        let (c, s) = (0.6f64, 0.8f64);
+        let a = Matrix::new(3, 3, vec![c, -s, 0.0, s, c, 0.0, 0.0, 0.0, 2.0]);
+        let eigs = a.complex_eigenvalues()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.re, e.im))
+            .collect::<Vec<_>>();
+
+        assert_eigenvalues(&eigs, &[(2.0, 0.0), (c, s), (c, -s)], 1e-10);
+    }
+
+    #[test]
+    fn test_real_complex_eigenvalues() {
+        let a = Matrix::new(5,
+                            5,
+                            vec![1f64, 2.0, 3.0, 4.0, 5.0, 2.0, 4.0, 1.0, 2.0, 1.0, 3.0, 1.0,
+                                 7.0, 1.0, 1.0, 4.0, 2.0, 1.0, -1.0, 3.0, 5.0, 1.0, 1.0, 3.0, 2.0]);
+        let eigs = a.complex_eigenvalues()
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.re, e.im))
+            .collect::<Vec<_>>();
+
+        assert_eigenvalues(&eigs,
+                           &[(12.1739747429271112, 0.0),
+                             (5.2681047320525831, 0.0),
+                             (-4.4942269799769843, 0.0),
+                             (2.9279675877385123, 0.0),
+                             (-2.8758200827412224, 0.0)],
+                           1e-10);
+    }
+
+    #[test]
+    fn test_1_by_1_complex_eigenvalues() {
+        let a = Matrix::new(1, 1, vec![3.0f64]);
+        let eigs = a.complex_eigenvalues().unwrap();
+
+        assert_eq!(eigs.len(), 1);
+        assert_eq!(eigs[0].re, 3.0);
+        assert_eq!(eigs[0].im, 0.0);
+    }
+
+    fn validate_schur(a: &Matrix<f64>, q: &Matrix<f64>, t: &Matrix<f64>) {
+        let n = a.rows();
+
+        // Q is orthogonal
+        let identity = Matrix::<f64>::identity(n);
+        let qtq = q.transpose() * q;
+        assert!(qtq.data().iter().zip(identity.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        // T is quasi-upper-triangular
+        for i in 0..n {
+            for j in 0..i {
+                if i == j + 1 {
+                    assert!(t[[i, j]] == 0.0 || ((j == 0 || t[[j, j - 1]] == 0.0) &&
+                                                 (i + 1 == n || t[[i + 1, i]] == 0.0)));
+                } else {
+                    assert_eq!(t[[i, j]], 0.0);
+                }
+            }
+        }
+
+        // A = Q T Q^T
+        let b = q * t * q.transpose();
+        assert!(a.data().iter().zip(b.data().iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_schur() {
+        let a = Matrix::new(5,
+                            5,
+                            vec![4.0f64, -1.0, 2.0, 0.5, 3.0, 1.0, 2.0, -3.0, 1.0, 0.0, -2.0, 5.0,
+                                 1.0, 2.0, -1.0, 0.0, 1.0, -4.0, 3.0, 2.0, 1.0, 0.0, 2.0, -1.0, 1.0]);
+        let (q, t) = a.clone().schur().unwrap();
+
+        validate_schur(&a, &q, &t);
+    }
+
+    #[test]
+    fn test_schur_complex_block() {
+        let (c, s) = (0.6f64, 0.8f64);
+        let a = Matrix::new(3, 3, vec![c, -s, 1.0, s, c, 2.0, 0.0, 0.0, 2.0]);
+        let (q, t) = a.clone().schur().unwrap();
+
+        validate_schur(&a, &q, &t);
+        assert!(t[[1, 0]] != 0.0 || t[[2, 1]] != 0.0);
+    }
+
+    #[test]
+    fn test_reorder_schur_real() {
+        let a = Matrix::new(4,
+                            4,
+                            vec![1.0f64, 2.0, 0.0, 1.0, 0.0, -3.0, 1.0, 2.0, 0.0, 0.0, 4.0, 1.0,
+                                 0.0, 0.0, 0.0, -2.0]);
+        let (mut q, mut t) = a.clone().schur().unwrap();
+        let k = t.reorder_schur(&mut q, |e| e.re < 0.0).unwrap();
+
+        validate_schur(&a, &q, &t);
+        assert_eq!(k, 2);
+        assert!((t[[0, 0]] + 3.0).abs() < 1e-12);
+        assert!((t[[1, 1]] + 2.0).abs() < 1e-12);
+        assert!(t[[2, 2]] > 0.0 && t[[3, 3]] > 0.0);
+    }
+
+    #[test]
+    fn test_reorder_schur_complex() {
+        // Eigenvalues 3, -1, and the complex pair 1 ± 2i.
+        let a = Matrix::new(4,
+                            4,
+                            vec![3.0f64, 1.0, 2.0, -1.0, 0.0, -1.0, 1.0, 0.5, 0.0, 0.0, 1.0, -4.0,
+                                 0.0, 0.0, 1.0, 1.0]);
+        let (mut q, mut t) = a.clone().schur().unwrap();
+        let k = t.reorder_schur(&mut q, |e| e.im != 0.0).unwrap();
+
+        validate_schur(&a, &q, &t);
+        assert_eq!(k, 2);
+        assert!(t[[1, 0]] != 0.0);
+        assert!((t[[0, 0]] + t[[1, 1]] - 2.0).abs() < 1e-10);
+
+        // The complex pair now sits at the top, followed by the real eigenvalues.
+        let eigs = super::quasi_triangular_eigenvalues(&t);
+        assert!((eigs[0].re - 1.0).abs() < 1e-10 && (eigs[0].im - 2.0).abs() < 1e-10);
+        assert!((eigs[2].re - 3.0).abs() < 1e-10 && (eigs[3].re + 1.0).abs() < 1e-10);
+    }
+
+    fn validate_eigenvectors(a: &Matrix<f64>,
+                             eigenvalues: &[Complex<f64>],
+                             vectors: &Matrix<Complex<f64>>,
+                             left: bool) {
+        let n = a.rows();
+
+        for k in 0..n {
+            let norm = (0..n).fold(0.0, |acc, i| acc + vectors[[i, k]].norm_sqr());
+            assert!((norm - 1.0).abs() < 1e-12);
+
+            for i in 0..n {
+                // Either (A v)_i or (u^H A)_i
+                let av = (0..n).fold(Complex::new(0.0, 0.0), |acc, j| if left {
+                    acc + vectors[[j, k]].conj().scale(a[[j, i]])
+                } else {
+                    acc + vectors[[j, k]].scale(a[[i, j]])
+                });
+                let v_i = if left { vectors[[i, k]].conj() } else { vectors[[i, k]] };
+
+                assert!((av - eigenvalues[k] * v_i).norm() < 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_complex_eigendecomp() {
+        // Eigenvalues 3, -1, and the complex pair 1 ± 2i.
+        let a = Matrix::new(4,
+                            4,
+                            vec![3.0f64, 1.0, 2.0, -1.0, 0.0, -1.0, 1.0, 0.5, 0.0, 0.0, 1.0, -4.0,
+                                 0.0, 0.0, 1.0, 1.0]);
+        let (eigenvalues, vectors) = a.complex_eigendecomp().unwrap();
+
+        validate_eigenvectors(&a, &eigenvalues, &vectors, false);
+    }
+
+    #[test]
+    fn test_complex_left_eigendecomp() {
+        let a = Matrix::new(5,
+                            5,
+                            vec![4.0f64, -1.0, 2.0, 0.5, 3.0, 1.0, 2.0, -3.0, 1.0, 0.0, -2.0, 5.0,
+                                 1.0, 2.0, -1.0, 0.0, 1.0, -4.0, 3.0, 2.0, 1.0, 0.0, 2.0, -1.0, 1.0]);
+        let (eigenvalues, vectors) = a.complex_left_eigendecomp().unwrap();
+        validate_eigenvectors(&a, &eigenvalues, &vectors, true);
+
+        let (eigenvalues, vectors) = a.complex_eigendecomp().unwrap();
+        validate_eigenvectors(&a, &eigenvalues, &vectors, false);
+    }
+
+    #[test]
+    fn test_complex_eigendecomp_conjugate_pairs() {
+        let (c, s) = (0.6f64, 0.8f64);
+        let a = Matrix::new(3, 3, vec![c, -s, 1.0, s, c, 2.0, 0.0, 0.0, 2.0]);
+        let (eigenvalues, vectors) = a.complex_eigendecomp().unwrap();
+
+        validate_eigenvectors(&a, &eigenvalues, &vectors, false);
+
+        let k = eigenvalues.iter().position(|e| e.im > 0.0).unwrap();
+        assert_eq!(eigenvalues[k + 1], eigenvalues[k].conj());
+        for i in 0..3 {
+            assert_eq!(vectors[[i, k + 1]], vectors[[i, k]].conj());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_complex_eigenvalues() {
+        let a = Matrix::new(2, 3, vec![1.0f64; 6]);
+
+        let _ = a.complex_eigenvalues();
+    }
+}
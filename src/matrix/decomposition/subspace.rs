@@ -0,0 +1,195 @@
+//! Numerical rank and the fundamental subspaces of a matrix.
+//!
+//! All of these are computed from the singular value decomposition. A
+//! singular value is treated as zero when it is not greater than the
+//! tolerance, which defaults to `max(m, n) * eps * σ_max`.
+
+use std::any::Any;
+
+use matrix::{Matrix, BaseMatrix};
+use error::Error;
+use epsilon::MachineEpsilon;
+
+use libnum::{Float, Signed};
+
+use super::numerical_rank;
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Computes the numerical rank of the matrix.
+    ///
+    /// This is the number of singular values greater than `tol`. If `tol`
+    /// is `None` the tolerance is `max(m, n) * eps * σ_max`, where `eps` is
+    /// the machine epsilon and `σ_max` is the largest singular value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// // The third row is the sum of the first two.
+    /// let a = Matrix::new(3, 3, vec![1.0f64, 2.0, 3.0,
+    ///                                4.0, 5.0, 6.0,
+    ///                                5.0, 7.0, 9.0]);
+    ///
+    /// assert_eq!(a.rank(None).unwrap(), 2);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The singular values could not be computed.
+    pub fn rank(&self, tol: Option<T>) -> Result<usize, Error> {
+        let s = try!(self.singular_values());
+        Ok(numerical_rank(self.rows, self.cols, s.into_vec(), tol))
+    }
+
+    /// Computes an orthonormal basis for the null space of the matrix.
+    ///
+    /// Returns a matrix whose columns are an orthonormal basis of the
+    /// vectors `x` with `Ax = 0`. For an `m` x `n` matrix of rank `r` this
+    /// matrix is `n` x `(n - r)`. The rank is determined as in `rank`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let a = Matrix::new(2, 3, vec![1.0f64, 1.0, 0.0,
+    ///                                0.0, 0.0, 1.0]);
+    ///
+    /// let null = a.null_space(None).unwrap();
+    /// assert_eq!((null.rows(), null.cols()), (3, 1));
+    ///
+    /// let product = &a * &null;
+    /// assert!(product.data().iter().all(|x| x.abs() < 1e-12));
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The SVD could not be computed.
+    pub fn null_space(&self, tol: Option<T>) -> Result<Matrix<T>, Error> {
+        let (m, n) = (self.rows, self.cols);
+        let (s, _, v) = try!(self.clone().full_svd());
+        let rank = numerical_rank(m, n, s.diag().into_vec(), tol);
+
+        Ok(Matrix::from_fn(n, n - rank, |j, i| v[[i, rank + j]]))
+    }
+
+    /// Computes an orthonormal basis for the column space (range) of the matrix.
+    ///
+    /// Returns a matrix whose columns are an orthonormal basis of the
+    /// vectors `Ax`. For an `m` x `n` matrix of rank `r` this matrix is
+    /// `m` x `r`. The rank is determined as in `rank`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// // Both columns are multiples of (1, 2, 2).
+    /// let a = Matrix::new(3, 2, vec![1.0f64, -2.0,
+    ///                                2.0, -4.0,
+    ///                                2.0, -4.0]);
+    ///
+    /// let range = a.column_space(None).unwrap();
+    /// assert_eq!((range.rows(), range.cols()), (3, 1));
+    ///
+    /// let expected = [1.0 / 3.0, 2.0 / 3.0, 2.0 / 3.0];
+    /// let sign = range[[0, 0]].signum();
+    /// assert!(range.data().iter().zip(expected.iter()).all(|(x, y)| (sign * x - y).abs() < 1e-12));
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The SVD could not be computed.
+    pub fn column_space(&self, tol: Option<T>) -> Result<Matrix<T>, Error> {
+        let (m, n) = (self.rows, self.cols);
+        let (s, u, _) = try!(self.clone().svd());
+        let rank = numerical_rank(m, n, s.diag().into_vec(), tol);
+
+        Ok(Matrix::from_fn(m, rank, |j, i| u[[i, j]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+
+    fn assert_orthonormal_columns(q: &Matrix<f64>) {
+        let qtq = q.transpose() * q;
+        for i in 0..q.cols() {
+            for j in 0..q.cols() {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((qtq[[i, j]] - expected).abs() < 1e-12);
+            }
+        }
+    }
+
+    /// A 4 x 5 matrix of rank 2.
+    fn rank_two_matrix() -> Matrix<f64> {
+        let b = Matrix::new(4, 2, vec![1.0, 0.0, 2.0, 1.0, -1.0, 3.0, 0.0, 1.0]);
+        let c = Matrix::new(2, 5, vec![1.0, 2.0, 0.0, -1.0, 1.0, 0.0, 1.0, 1.0, 2.0, -3.0]);
+        b * c
+    }
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(Matrix::<f64>::identity(4).rank(None).unwrap(), 4);
+        assert_eq!(Matrix::<f64>::zeros(3, 2).rank(None).unwrap(), 0);
+
+        let a = rank_two_matrix();
+        assert_eq!(a.rank(None).unwrap(), 2);
+        assert_eq!(a.transpose().rank(None).unwrap(), 2);
+
+        let d = Matrix::from_diag(&[1.0, 1e-4, 1e-20]);
+        assert_eq!(d.rank(None).unwrap(), 2);
+        assert_eq!(d.rank(Some(1e-3)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_null_space() {
+        let a = rank_two_matrix();
+
+        let null = a.null_space(None).unwrap();
+        assert_eq!((null.rows(), null.cols()), (5, 3));
+        assert_orthonormal_columns(&null);
+        assert!((&a * &null).data().iter().all(|x| x.abs() < 1e-12));
+
+        let left_null = a.transpose().null_space(None).unwrap();
+        assert_eq!((left_null.rows(), left_null.cols()), (4, 2));
+        assert_orthonormal_columns(&left_null);
+        assert!((left_null.transpose() * &a).data().iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_null_space_full_rank() {
+        let a = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 7.0]);
+        let null = a.null_space(None).unwrap();
+
+        assert_eq!((null.rows(), null.cols()), (2, 0));
+    }
+
+    #[test]
+    fn test_column_space() {
+        let a = rank_two_matrix();
+
+        let range = a.column_space(None).unwrap();
+        assert_eq!((range.rows(), range.cols()), (4, 2));
+        assert_orthonormal_columns(&range);
+
+        // Projecting the columns onto the range leaves them unchanged.
+        let projected = &range * (range.transpose() * &a);
+        assert!(projected.data().iter().zip(a.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        // The range is orthogonal to the left null space.
+        let left_null = a.transpose().null_space(None).unwrap();
+        assert!((left_null.transpose() * &range).data().iter().all(|x| x.abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_subspaces_zero_matrix() {
+        let a = Matrix::<f64>::zeros(3, 2);
+
+        assert_eq!(a.column_space(None).unwrap().cols(), 0);
+        assert_eq!(a.null_space(None).unwrap(), Matrix::identity(2));
+    }
+}
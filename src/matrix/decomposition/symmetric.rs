@@ -0,0 +1,245 @@
+//! Eigendecomposition of real symmetric matrices.
+//!
+//! The matrix is reduced to tridiagonal form using Householder reflections
+//! and the tridiagonal matrix is diagonalized with the implicit symmetric QR
+//! algorithm using Wilkinson shifts.
+//!
+//! References:
+//! 1. Matrix Computations, Golub and Van Loan, Section 8.3.
+
+use std::any::Any;
+use std::cmp;
+
+use matrix::{Matrix, BaseMatrix};
+use error::{Error, ErrorKind};
+use epsilon::MachineEpsilon;
+
+use libnum::{Float, Signed};
+
+use super::{hessenberg_reduction, rotate_cols, givens_rot};
+
+impl<T: Any + Float + Signed + MachineEpsilon> Matrix<T> {
+    /// Eigendecomposition of a real symmetric matrix.
+    ///
+    /// Returns a Vec of eigenvalues sorted in ascending order, and a matrix
+    /// with the corresponding orthonormal eigenvectors as the columns.
+    ///
+    /// Unlike `eigendecomp` the eigenvectors are always correct, and the
+    /// computation is faster. The matrix is assumed to be symmetric,
+    /// this is not checked.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(3,3,vec![3.0f64,2.,4.,2.,0.,2.,4.,2.,3.]);
+    ///
+    /// let (e, m) = a.symmetric_eigendecomp().expect("We should be able to compute this eigendecomp!");
+    ///
+    /// assert!((e[0] + 1.).abs() < 1e-12);
+    /// assert!((e[1] + 1.).abs() < 1e-12);
+    /// assert!((e[2] - 8.).abs() < 1e-12);
+    /// println!("{:?}", m.data());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The symmetric QR iteration fails to converge.
+    pub fn symmetric_eigendecomp(&self) -> Result<(Vec<T>, Matrix<T>), Error> {
+        let n = self.rows();
+        assert!(n == self.cols,
+                "Matrix must be square for symmetric eigendecomp.");
+
+        let mut t = self.clone();
+        let mut q = Matrix::identity(n);
+        hessenberg_reduction(&mut t, Some(&mut q));
+
+        let mut diag = (0..n).map(|i| t[[i, i]]).collect::<Vec<_>>();
+        let mut off_diag = (1..n).map(|i| t[[i, i - 1]]).collect::<Vec<_>>();
+
+        try!(symmetric_tridiagonal_qr(&mut diag, &mut off_diag, &mut q));
+
+        // Sort the eigenvalues and their eigenvectors in ascending order.
+        let mut indices = (0..n).collect::<Vec<_>>();
+        indices.sort_by(|&i, &j| {
+            diag[i].partial_cmp(&diag[j]).expect("All eigenvalues should be finite, and thus sortable.")
+        });
+
+        let eigenvalues = indices.iter().map(|&i| diag[i]).collect();
+        Ok((eigenvalues, q.select_cols(&indices)))
+    }
+}
+
+/// Diagonalizes the symmetric tridiagonal matrix with diagonal `diag` and
+/// subdiagonal `off_diag` using the implicit symmetric QR algorithm with
+/// Wilkinson shifts.
+///
+/// On completion `diag` holds the (unsorted) eigenvalues. The rotations
+/// are accumulated into the columns of `q`.
+fn symmetric_tridiagonal_qr<T>(diag: &mut [T], off_diag: &mut [T], q: &mut Matrix<T>) -> Result<(), Error>
+    where T: Float + MachineEpsilon
+{
+    let n = diag.len();
+    if n < 2 {
+        return Ok(());
+    }
+
+    let eps = <T as MachineEpsilon>::epsilon();
+    let two = T::one() + T::one();
+    let norm = diag.iter().chain(off_diag.iter()).fold(T::zero(), |acc, &x| acc.max(x.abs()));
+    let max_iterations = 30 * cmp::max(n, 10);
+    let mut iterations = 0;
+
+    // The final index of the unreduced block
+    let mut hi = n - 1;
+
+    while hi > 0 {
+        for i in 0..hi {
+            let mut s = diag[i].abs() + diag[i + 1].abs();
+            if s == T::zero() {
+                s = norm;
+            }
+
+            if off_diag[i].abs() <= eps * s {
+                off_diag[i] = T::zero();
+            }
+        }
+
+        if off_diag[hi - 1] == T::zero() {
+            hi -= 1;
+            continue;
+        }
+
+        let mut lo = hi - 1;
+        while lo > 0 && off_diag[lo - 1] != T::zero() {
+            lo -= 1;
+        }
+
+        iterations += 1;
+        if iterations > max_iterations {
+            return Err(Error::new(ErrorKind::DecompFailure,
+                                  "Symmetric QR iteration did not converge."));
+        }
+
+        // The Wilkinson shift is the eigenvalue of the trailing 2x2 block
+        // closest to the final diagonal entry.
+        let delta = (diag[hi - 1] - diag[hi]) / two;
+        let b = off_diag[hi - 1];
+        let denom = if delta >= T::zero() {
+            delta + delta.hypot(b)
+        } else {
+            delta - delta.hypot(b)
+        };
+        let mu = diag[hi] - b * b / denom;
+
+        let mut x = diag[lo] - mu;
+        let mut z = off_diag[lo];
+
+        // Chase the bulge down the unreduced block.
+        for k in lo..hi {
+            let (c, s) = givens_rot(x, z);
+
+            if k > lo {
+                off_diag[k - 1] = c * x + s * z;
+            }
+
+            let (d_k, d_k1, e_k) = (diag[k], diag[k + 1], off_diag[k]);
+            diag[k] = c * c * d_k + two * c * s * e_k + s * s * d_k1;
+            diag[k + 1] = s * s * d_k - two * c * s * e_k + c * c * d_k1;
+            off_diag[k] = c * s * (d_k1 - d_k) + (c * c - s * s) * e_k;
+
+            if k + 1 < hi {
+                x = off_diag[k];
+                z = s * off_diag[k + 1];
+                off_diag[k + 1] = c * off_diag[k + 1];
+            }
+
+            let rows = q.rows();
+            rotate_cols(q, k, k + 1, c, s, 0..rows);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use matrix::{Matrix, BaseMatrix};
+
+    fn validate_symmetric_eigendecomp(a: &Matrix<f64>, eigenvalues: &[f64], q: &Matrix<f64>) {
+        let n = a.rows();
+
+        // The eigenvalues are sorted
+        assert!(eigenvalues.windows(2).all(|w| w[0] <= w[1]));
+
+        // The eigenvectors are orthonormal
+        let identity = Matrix::<f64>::identity(n);
+        let qtq = q.transpose() * q;
+        assert!(qtq.data().iter().zip(identity.data().iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+
+        // A Q = Q Λ
+        let aq = a * q;
+        let q_lambda = q * Matrix::from_diag(eigenvalues);
+        assert!(aq.data().iter().zip(q_lambda.data().iter()).all(|(x, y)| (x - y).abs() < 1e-10));
+    }
+
+    #[test]
+    fn test_symmetric_eigendecomp_repeated_eigenvalues() {
+        let a = Matrix::new(3, 3, vec![3., 2., 4., 2., 0., 2., 4., 2., 3.]);
+        let (eigenvalues, q) = a.symmetric_eigendecomp().unwrap();
+
+        validate_symmetric_eigendecomp(&a, &eigenvalues, &q);
+
+        let expected = vec![-1., -1., 8.];
+        assert!(eigenvalues.iter().zip(expected.iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_symmetric_eigendecomp_5_by_5() {
+        let a = Matrix::new(5,
+                            5,
+                            vec![1f64, 2.0, 3.0, 4.0, 5.0, 2.0, 4.0, 1.0, 2.0, 1.0, 3.0, 1.0,
+                                 7.0, 1.0, 1.0, 4.0, 2.0, 1.0, -1.0, 3.0, 5.0, 1.0, 1.0, 3.0, 2.0]);
+        let (eigenvalues, q) = a.symmetric_eigendecomp().unwrap();
+
+        validate_symmetric_eigendecomp(&a, &eigenvalues, &q);
+
+        let expected = vec![-4.4942269799769843,
+                            -2.8758200827412224,
+                            2.9279675877385123,
+                            5.2681047320525831,
+                            12.1739747429271112];
+        assert!(eigenvalues.iter().zip(expected.iter()).all(|(x, y)| (x - y).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_symmetric_eigendecomp_diagonal() {
+        let a = Matrix::from_diag(&[3., -1., 2., 0.]);
+        let (eigenvalues, q) = a.symmetric_eigendecomp().unwrap();
+
+        validate_symmetric_eigendecomp(&a, &eigenvalues, &q);
+        assert_eq!(eigenvalues, vec![-1., 0., 2., 3.]);
+    }
+
+    #[test]
+    fn test_symmetric_eigendecomp_1_by_1() {
+        let a = Matrix::new(1, 1, vec![3.]);
+        let (eigenvalues, q) = a.symmetric_eigendecomp().unwrap();
+
+        assert_eq!(eigenvalues, vec![3.]);
+        assert_eq!(*q.data(), vec![1.]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_non_square_symmetric_eigendecomp() {
+        let a = Matrix::new(2, 3, vec![1.0; 6]);
+
+        let _ = a.symmetric_eigendecomp();
+    }
+}
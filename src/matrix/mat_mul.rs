@@ -1,4 +1,4 @@
-use super::{Matrix, MatrixSlice, MatrixSliceMut, BaseMatrix};
+use super::{Matrix, MatrixSlice, MatrixSliceMut, BaseMatrix, BaseMatrixMut};
 
 use std::any::{Any, TypeId};
 use std::ops::{Add, Mul};
@@ -14,6 +14,105 @@ fn same_type<A, B>() -> bool
     TypeId::of::<A>() == TypeId::of::<B>()
 }
 
+/// Computes `C = alpha * AB + beta * C` in place.
+///
+/// This goes through the `matrixmultiply` kernels for `f32` and `f64`,
+/// and is used for the block updates in the blocked decompositions.
+///
+/// # Panics
+///
+/// - The dimensions of `A`, `B` and `C` do not agree.
+pub fn gemm<T, A, B, C>(alpha: T, a: &A, b: &B, beta: T, c: &mut C)
+    where T: Any + Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>,
+          A: BaseMatrix<T>,
+          B: BaseMatrix<T>,
+          C: BaseMatrixMut<T>
+{
+    gemm_transposed(alpha, a, false, b, false, beta, c)
+}
+
+/// Computes `C = alpha * op(A) op(B) + beta * C` in place, where `op(A)`
+/// is `Aᵀ` if `transpose_a` is true and `A` otherwise, and likewise for `B`.
+///
+/// The transposes are never formed, the operands are read with their
+/// row and column strides swapped.
+///
+/// # Panics
+///
+/// - The dimensions of `op(A)`, `op(B)` and `C` do not agree.
+pub fn gemm_transposed<T, A, B, C>(alpha: T,
+                                   a: &A,
+                                   transpose_a: bool,
+                                   b: &B,
+                                   transpose_b: bool,
+                                   beta: T,
+                                   c: &mut C)
+    where T: Any + Copy + Zero + Add<T, Output = T> + Mul<T, Output = T>,
+          A: BaseMatrix<T>,
+          B: BaseMatrix<T>,
+          C: BaseMatrixMut<T>
+{
+    // The dimensions and strides of op(A) and op(B).
+    let (m, k, rsa, csa) = if transpose_a {
+        (a.cols(), a.rows(), 1, a.row_stride() as isize)
+    } else {
+        (a.rows(), a.cols(), a.row_stride() as isize, 1)
+    };
+    let (k_b, n, rsb, csb) = if transpose_b {
+        (b.cols(), b.rows(), 1, b.row_stride() as isize)
+    } else {
+        (b.rows(), b.cols(), b.row_stride() as isize, 1)
+    };
+
+    assert!(k == k_b && m == c.rows() && n == c.cols(),
+            "Matrix dimensions do not agree.");
+
+    if m == 0 || n == 0 {
+        return;
+    }
+
+    if same_type::<T, f32>() {
+        unsafe {
+            matrixmultiply::sgemm(m, k, n,
+                                  *(&alpha as *const T as *const f32),
+                                  a.as_ptr() as *const _,
+                                  rsa, csa,
+                                  b.as_ptr() as *const _,
+                                  rsb, csb,
+                                  *(&beta as *const T as *const f32),
+                                  c.as_mut_ptr() as *mut _,
+                                  c.row_stride() as isize, 1);
+        }
+    } else if same_type::<T, f64>() {
+        unsafe {
+            matrixmultiply::dgemm(m, k, n,
+                                  *(&alpha as *const T as *const f64),
+                                  a.as_ptr() as *const _,
+                                  rsa, csa,
+                                  b.as_ptr() as *const _,
+                                  rsb, csb,
+                                  *(&beta as *const T as *const f64),
+                                  c.as_mut_ptr() as *mut _,
+                                  c.row_stride() as isize, 1);
+        }
+    } else {
+        unsafe {
+            for i in 0..m {
+                for j in 0..n {
+                    let mut sum = T::zero();
+                    for l in 0..k {
+                        let a_il = if transpose_a { a.get_unchecked([l, i]) } else { a.get_unchecked([i, l]) };
+                        let b_lj = if transpose_b { b.get_unchecked([j, l]) } else { b.get_unchecked([l, j]) };
+                        sum = sum + *a_il * *b_lj;
+                    }
+                    let c_ij = c.get_unchecked_mut([i, j]);
+                    *c_ij = alpha * sum + beta * *c_ij;
+                }
+            }
+        }
+    }
+}
+
 macro_rules! mat_mul_general (
     ($mat:ident) => (
 
@@ -418,4 +517,39 @@ mod tests {
         assert_eq!(e[[1, 0]], 19);
         assert_eq!(e[[1, 1]], 28);
     }
+
+    #[test]
+    fn gemm_transposed_slices() {
+        use super::gemm_transposed;
+
+        // Strided slices, so that the row stride differs from the column count.
+        let a = Matrix::new(3, 4, (0..12).collect::<Vec<i64>>());
+        let b = Matrix::new(2, 4, vec![1i64, -1, 2, 0, 3, 1, 0, -2]);
+        let a_slice = MatrixSlice::from_matrix(&a, [0, 1], 2, 3);
+        let b_slice = MatrixSlice::from_matrix(&b, [0, 1], 2, 3);
+
+        // C = 2 AᵀB + C and C = 2 ABᵀ + C, compared with explicit transposes.
+        let mut c = Matrix::new(3, 3, vec![1i64; 9]);
+        gemm_transposed(2, &a_slice, true, &b_slice, false, 1, &mut c);
+        assert_eq!(c, (a_slice.transpose() * b_slice) * 2 + 1);
+
+        let mut c = Matrix::new(2, 2, vec![1i64; 4]);
+        gemm_transposed(2, &a_slice, false, &b_slice, true, 1, &mut c);
+        assert_eq!(c, (a_slice * b_slice.transpose()) * 2 + 1);
+
+        // The f32 and f64 kernels.
+        let a = Matrix::new(3, 4, (0..12).map(|x| x as f64).collect::<Vec<_>>());
+        let b = Matrix::new(2, 4, vec![1.0, -1.0, 2.0, 0.0, 3.0, 1.0, 0.0, -2.0]);
+        let a_slice = MatrixSlice::from_matrix(&a, [0, 1], 2, 3);
+        let b_slice = MatrixSlice::from_matrix(&b, [0, 1], 2, 3);
+
+        let mut c = Matrix::new(3, 3, vec![1.0; 9]);
+        gemm_transposed(2.0, &a_slice, true, &b_slice, false, 1.0, &mut c);
+        assert_eq!(c, (a_slice.transpose() * b_slice) * 2.0 + 1.0);
+
+        let a_32 = Matrix::new(3, 4, a.data().iter().map(|&x| x as f32).collect::<Vec<_>>());
+        let mut c = Matrix::new(4, 4, vec![0.0f32; 16]);
+        gemm_transposed(1.0, &a_32, true, &a_32, false, 0.0, &mut c);
+        assert_eq!(c, a_32.transpose() * &a_32);
+    }
 }
@@ -7,6 +7,7 @@
 //! via `BaseMatrix` and `BaseMatrixMut` trait.
 
 use std::any::Any;
+use std::cmp;
 use std::fmt;
 use std::marker::PhantomData;
 use libnum::{One, Zero, Float, FromPrimitive};
@@ -16,7 +17,7 @@ use error::{Error, ErrorKind};
 use utils;
 use vector::Vector;
 
-mod decomposition;
+pub mod decomposition;
 mod impl_ops;
 mod mat_mul;
 mod iter;
@@ -24,6 +25,9 @@ pub mod slice;
 
 pub use self::slice::{BaseMatrix, BaseMatrixMut};
 
+use self::decomposition::PartialPivLu;
+use self::mat_mul::{gemm, gemm_transposed};
+
 /// Matrix dimensions
 #[derive(Debug, Clone, Copy)]
 pub enum Axes {
@@ -406,6 +410,10 @@ impl<T: Any + Float> Matrix<T> {
     ///
     /// Requires a Vector `y` as input.
     ///
+    /// No warning is given when the matrix is ill-conditioned, in which
+    /// case the solution may be inaccurate. Use `solve_with_rcond` to also
+    /// get an estimate of the conditioning from the same decomposition.
+    ///
     /// # Examples
     ///
     /// ```
@@ -427,13 +435,82 @@ impl<T: Any + Float> Matrix<T> {
     ///
     /// # Failures
     ///
-    /// - The matrix cannot be decomposed into an LUP form to solve.
     /// - There is no valid solution as the matrix is singular.
     pub fn solve(&self, y: Vector<T>) -> Result<Vector<T>, Error> {
-        let (l, u, p) = try!(self.lup_decomp());
+        PartialPivLu::decompose(self.clone()).solve(y)
+    }
+
+    /// Solves the equation `Ax = y`, and estimates the reciprocal of the
+    /// condition number of the matrix.
+    ///
+    /// Returns the tuple `(x, rcond)`. Both are computed from a single
+    /// `PartialPivLu` decomposition, see `PartialPivLu::rcond`. A value of
+    /// `rcond` close to the machine epsilon means that `x` may have no
+    /// correct digits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let a = Matrix::new(2, 2, vec![1.0f64, 1.0, 1.0, 1.0 + 1e-14]);
+    /// let y = Vector::new(vec![2.0, 2.0]);
+    ///
+    /// let (x, rcond) = a.solve_with_rcond(y).unwrap();
+    ///
+    /// // The system is solved, but x may have only two or three correct digits.
+    /// assert_eq!(x.size(), 2);
+    /// assert!(rcond < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix column count and vector size are different.
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - There is no valid solution as the matrix is singular.
+    pub fn solve_with_rcond(&self, y: Vector<T>) -> Result<(Vector<T>, T), Error> {
+        let lu = PartialPivLu::decompose(self.clone());
+        let x = try!(lu.solve(y));
+        Ok((x, lu.rcond()))
+    }
+
+    /// Solves the equation `AX = B` for all columns of `B` at once.
+    ///
+    /// The matrix is decomposed once with `PartialPivLu`, and the triangular
+    /// solves work on whole blocks of rows of `B`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::Matrix;
+    ///
+    /// let a = Matrix::new(2, 2, vec![2.0, 3.0, 1.0, 2.0]);
+    /// let b = Matrix::new(2, 3, vec![13.0, 2.0, 3.0,
+    ///                                8.0, 1.0, 2.0]);
+    ///
+    /// let x = a.solve_matrix(&b).unwrap();
+    ///
+    /// assert_eq!(*x.data(), vec![2.0, 1.0, 0.0,
+    ///                            3.0, 0.0, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    /// - The row counts of the matrix and `B` are different.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix is singular.
+    pub fn solve_matrix<M: BaseMatrix<T>>(&self, b: &M) -> Result<Matrix<T>, Error> {
+        assert!(self.rows == b.rows(),
+                "Right-hand side must have the same number of rows as the matrix.");
 
-        let b = try!(forward_substitution(&l, p * y));
-        back_substitution(&u, b)
+        PartialPivLu::decompose(self.clone()).solve_matrix(b.as_slice().into_matrix())
     }
 
     /// Computes the inverse of the matrix.
@@ -457,44 +534,14 @@ impl<T: Any + Float> Matrix<T> {
     ///
     /// # Failures
     ///
-    /// - The matrix could not be LUP decomposed.
     /// - The matrix has zero determinant.
     pub fn inverse(&self) -> Result<Matrix<T>, Error> {
         assert!(self.rows == self.cols, "Matrix is not square.");
 
-        let mut inv_t_data = Vec::<T>::new();
-        let (l, u, p) = try!(self.lup_decomp().map_err(|_| {
+        PartialPivLu::decompose(self.clone()).inverse().map_err(|_| {
             Error::new(ErrorKind::DecompFailure,
-                       "Could not compute LUP factorization for inverse.")
-        }));
-
-        let mut d = T::one();
-
-        unsafe {
-            for i in 0..l.cols {
-                d = d * *l.get_unchecked([i, i]);
-                d = d * *u.get_unchecked([i, i]);
-            }
-        }
-
-        if d == T::zero() {
-            return Err(Error::new(ErrorKind::DecompFailure,
-                                  "Matrix is singular and cannot be inverted."));
-        }
-
-        for i in 0..self.rows {
-            let mut id_col = vec![T::zero(); self.cols];
-            id_col[i] = T::one();
-
-            let b = forward_substitution(&l, &p * Vector::new(id_col))
-                .expect("Matrix is singular AND has non-zero determinant!?");
-            inv_t_data.append(&mut back_substitution(&u, b)
-                .expect("Matrix is singular AND has non-zero determinant!?")
-                .into_vec());
-
-        }
-
-        Ok(Matrix::new(self.rows, self.cols, inv_t_data).transpose())
+                       "Matrix is singular and cannot be inverted.")
+        })
     }
 
     /// Computes the determinant of the matrix.
@@ -542,24 +589,7 @@ impl<T: Any + Float> Matrix<T> {
             (self[[0, 1]] * self[[1, 0]] * self[[2, 2]]) -
             (self[[0, 2]] * self[[1, 1]] * self[[2, 0]])
         } else {
-            let (l, u, p) = match self.lup_decomp() {
-                Ok(x) => x,
-                Err(ref e) if *e.kind() == ErrorKind::DivByZero => return T::zero(),
-                _ => { panic!("Could not compute LUP decomposition."); }
-            };
-
-            let mut d = T::one();
-
-            unsafe {
-                for i in 0..l.cols {
-                    d = d * *l.get_unchecked([i, i]);
-                    d = d * *u.get_unchecked([i, i]);
-                }
-            }
-
-            let sgn = parity(&p);
-
-            sgn * d
+            PartialPivLu::decompose(self.clone()).det()
         }
     }
 }
@@ -761,31 +791,223 @@ fn forward_substitution<T, M>(m: &M, y: Vector<T>) -> Result<Vector<T>, Error>
     Ok(Vector::new(x))
 }
 
-/// Computes the parity of a permutation matrix.
-fn parity<T, M>(m: &M) -> T
+/// The number of rows in each block of the blocked triangular solves.
+const TRIANGULAR_SOLVE_BLOCK_SIZE: usize = 64;
+
+/// Checks that the diagonal of a triangular matrix is not too close to zero.
+fn check_triangular_diagonal<T, M>(m: &M) -> Result<(), Error>
     where T: Any + Float,
           M: BaseMatrix<T>
 {
-    let mut visited = vec![false; m.rows()];
-    let mut sgn = T::one();
-
-    for k in 0..m.rows() {
-        if !visited[k] {
-            let mut next = k;
-            let mut len = 0;
-
-            while !visited[next] {
-                len += 1;
-                visited[next] = true;
-                next = utils::find(&m.get_row(next).unwrap(), T::one());
+    let tol = T::min_positive_value() + T::min_positive_value();
+
+    for i in 0..m.rows() {
+        if unsafe { m.get_unchecked([i, i]) }.abs() < tol {
+            return Err(Error::new(ErrorKind::AlgebraFailure,
+                                  "Linear system cannot be solved (matrix is singular)."));
+        }
+    }
+
+    Ok(())
+}
+
+/// Back substitution with many right-hand sides.
+///
+/// Overwrites `x` with the solution of `UX = X`, where U is the upper
+/// triangle of `m`. The diagonal blocks are solved with row operations
+/// and the remaining rows are updated with matrix multiplication.
+fn back_substitution_in_place<T, M>(m: &M, x: &mut Matrix<T>)
+    where T: Any + Float,
+          M: BaseMatrix<T>
+{
+    let cols = x.cols;
+
+    let mut block_end = m.rows();
+    while block_end > 0 {
+        let block_start = block_end.saturating_sub(TRIANGULAR_SOLVE_BLOCK_SIZE);
+
+        for i in (block_start..block_end).rev() {
+            let (rest, solved) = x.data.split_at_mut((i + 1) * cols);
+            let row = &mut rest[i * cols..];
+
+            for k in i + 1..block_end {
+                let u = unsafe { *m.get_unchecked([i, k]) };
+                if u != T::zero() {
+                    let offset = (k - i - 1) * cols;
+                    utils::in_place_vec_bin_op(row, &solved[offset..offset + cols], |x, &y| {
+                        *x = *x - u * y
+                    });
+                }
             }
 
-            if len % 2 == 0 {
-                sgn = -sgn;
+            let diag = unsafe { *m.get_unchecked([i, i]) };
+            for x_ij in row.iter_mut() {
+                *x_ij = *x_ij / diag;
             }
         }
+
+        if block_start > 0 {
+            let block = block_end - block_start;
+            let u_block = m.sub_slice([0, block_start], block_start, block);
+            let x_block = x.sub_slice([block_start, 0], block, cols);
+            let mut x_rest = x.sub_slice_mut([0, 0], block_start, cols);
+
+            gemm(-T::one(), &u_block, &x_block, T::one(), &mut x_rest);
+        }
+
+        block_end = block_start;
+    }
+}
+
+/// Forward substitution with many right-hand sides.
+///
+/// Overwrites `x` with the solution of `LX = X`, where L is the lower
+/// triangle of `m`, or the unit lower triangle if `unit_diagonal` is true.
+/// The diagonal blocks are solved with row operations and the remaining
+/// rows are updated with matrix multiplication.
+fn forward_substitution_in_place<T, M>(m: &M, x: &mut Matrix<T>, unit_diagonal: bool)
+    where T: Any + Float,
+          M: BaseMatrix<T>
+{
+    let n = m.rows();
+    let cols = x.cols;
+
+    let mut block_start = 0;
+    while block_start < n {
+        let block_end = cmp::min(block_start + TRIANGULAR_SOLVE_BLOCK_SIZE, n);
+
+        for i in block_start..block_end {
+            let (solved, rest) = x.data.split_at_mut(i * cols);
+            let row = &mut rest[..cols];
+
+            for k in block_start..i {
+                let l = unsafe { *m.get_unchecked([i, k]) };
+                if l != T::zero() {
+                    utils::in_place_vec_bin_op(row, &solved[k * cols..(k + 1) * cols], |x, &y| {
+                        *x = *x - l * y
+                    });
+                }
+            }
+
+            if !unit_diagonal {
+                let diag = unsafe { *m.get_unchecked([i, i]) };
+                for x_ij in row.iter_mut() {
+                    *x_ij = *x_ij / diag;
+                }
+            }
+        }
+
+        if block_end < n {
+            let block = block_end - block_start;
+            let l_block = m.sub_slice([block_end, block_start], n - block_end, block);
+            let x_block = x.sub_slice([block_start, 0], block, cols);
+            let mut x_rest = x.sub_slice_mut([block_end, 0], n - block_end, cols);
+
+            gemm(-T::one(), &l_block, &x_block, T::one(), &mut x_rest);
+        }
+
+        block_start = block_end;
+    }
+}
+
+/// Transposed back substitution with many right-hand sides.
+///
+/// Overwrites `x` with the solution of `UᵀX = X`, where U is the upper
+/// triangle of `m`. Uᵀ is lower triangular, so the rows are solved from
+/// the top, reading U by columns. The remaining rows are updated with
+/// matrix multiplication.
+fn back_substitution_transpose_in_place<T, M>(m: &M, x: &mut Matrix<T>)
+    where T: Any + Float,
+          M: BaseMatrix<T>
+{
+    let n = m.rows();
+    let cols = x.cols;
+
+    let mut block_start = 0;
+    while block_start < n {
+        let block_end = cmp::min(block_start + TRIANGULAR_SOLVE_BLOCK_SIZE, n);
+
+        for i in block_start..block_end {
+            let (solved, rest) = x.data.split_at_mut(i * cols);
+            let row = &mut rest[..cols];
+
+            for k in block_start..i {
+                let u = unsafe { *m.get_unchecked([k, i]) };
+                if u != T::zero() {
+                    utils::in_place_vec_bin_op(row, &solved[k * cols..(k + 1) * cols], |x, &y| {
+                        *x = *x - u * y
+                    });
+                }
+            }
+
+            let diag = unsafe { *m.get_unchecked([i, i]) };
+            for x_ij in row.iter_mut() {
+                *x_ij = *x_ij / diag;
+            }
+        }
+
+        if block_end < n {
+            let block = block_end - block_start;
+            let u_block = m.sub_slice([block_start, block_end], block, n - block_end);
+            let x_block = x.sub_slice([block_start, 0], block, cols);
+            let mut x_rest = x.sub_slice_mut([block_end, 0], n - block_end, cols);
+
+            gemm_transposed(-T::one(), &u_block, true, &x_block, false, T::one(), &mut x_rest);
+        }
+
+        block_start = block_end;
+    }
+}
+
+/// Transposed forward substitution with many right-hand sides.
+///
+/// Overwrites `x` with the solution of `LᵀX = X`, where L is the lower
+/// triangle of `m`, or the unit lower triangle if `unit_diagonal` is true.
+/// Lᵀ is upper triangular, so the rows are solved from the bottom, reading
+/// L by columns. The remaining rows are updated with matrix multiplication.
+fn forward_substitution_transpose_in_place<T, M>(m: &M, x: &mut Matrix<T>, unit_diagonal: bool)
+    where T: Any + Float,
+          M: BaseMatrix<T>
+{
+    let cols = x.cols;
+
+    let mut block_end = m.rows();
+    while block_end > 0 {
+        let block_start = block_end.saturating_sub(TRIANGULAR_SOLVE_BLOCK_SIZE);
+
+        for i in (block_start..block_end).rev() {
+            let (rest, solved) = x.data.split_at_mut((i + 1) * cols);
+            let row = &mut rest[i * cols..];
+
+            for k in i + 1..block_end {
+                let l = unsafe { *m.get_unchecked([k, i]) };
+                if l != T::zero() {
+                    let offset = (k - i - 1) * cols;
+                    utils::in_place_vec_bin_op(row, &solved[offset..offset + cols], |x, &y| {
+                        *x = *x - l * y
+                    });
+                }
+            }
+
+            if !unit_diagonal {
+                let diag = unsafe { *m.get_unchecked([i, i]) };
+                for x_ij in row.iter_mut() {
+                    *x_ij = *x_ij / diag;
+                }
+            }
+        }
+
+        if block_start > 0 {
+            let block = block_end - block_start;
+            let l_block = m.sub_slice([block_start, 0], block, block_start);
+            let x_block = x.sub_slice([block_start, 0], block, cols);
+            let mut x_rest = x.sub_slice_mut([0, 0], block_start, cols);
+
+            gemm_transposed(-T::one(), &l_block, true, &x_block, false, T::one(), &mut x_rest);
+        }
+
+        block_end = block_start;
     }
-    sgn
 }
 
 #[cfg(test)]
@@ -980,6 +1202,38 @@ mod tests {
         assert_eq!(x[1], 2.);
     }
 
+    #[test]
+    fn matrix_solve_matrix() {
+        let n = 100;
+        let a = Matrix::from_fn(n, n, |j, i| if i == j { 4.0 } else { 1.0 / ((i + 2 * j + 1) as f64) });
+        let b = Matrix::from_fn(n, 3, |j, i| (i * (j + 1)) as f64 / 10.0);
+
+        let x = a.solve_matrix(&b).unwrap();
+        assert_eq!((x.rows(), x.cols()), (n, 3));
+
+        for j in 0..3 {
+            let column = Vector::new((0..n).map(|i| b[[i, j]]).collect::<Vec<_>>());
+            let expected = a.solve(column).unwrap();
+            assert!((0..n).all(|i| abs(x[[i, j]] - expected[i]) < 1e-12));
+        }
+
+        let singular = Matrix::new(2, 2, vec![1., 2., 2., 4.]);
+        assert!(singular.solve_matrix(&Matrix::new(2, 1, vec![1., 2.])).is_err());
+    }
+
+    #[test]
+    fn matrix_solve_with_rcond() {
+        let a = Matrix::new(3, 3, vec![4.0, 1.0, 0.0, 1.0, 3.0, -1.0, 0.0, -1.0, 2.0]);
+        let y = Vector::new(vec![1.0, 2.0, 3.0]);
+
+        let (x, rcond) = a.solve_with_rcond(y.clone()).unwrap();
+        assert_eq!(x, a.solve(y).unwrap());
+        assert_eq!(rcond, a.rcond_estimate());
+
+        let singular = Matrix::new(2, 2, vec![1., 2., 2., 4.]);
+        assert!(singular.solve_with_rcond(Vector::new(vec![1., 2.])).is_err());
+    }
+
     #[test]
     fn create_mat_zeros() {
         let a = Matrix::<f32>::zeros(10, 10);
@@ -20,17 +20,21 @@
 //! ```
 
 use matrix::{Matrix, MatrixSlice, MatrixSliceMut, Rows, RowsMut, Axes};
+use matrix::decomposition::PartialPivLu;
 use matrix::{back_substitution, forward_substitution};
+use matrix::{back_substitution_in_place, forward_substitution_in_place, check_triangular_diagonal};
+use matrix::{back_substitution_transpose_in_place, forward_substitution_transpose_in_place};
 use vector::Vector;
 use utils;
-use libnum::{Zero, Float};
+use libnum::{Zero, One, Float, Signed};
+use epsilon::MachineEpsilon;
 use error::Error;
 
 use std::any::Any;
 use std::cmp::min;
 use std::marker::PhantomData;
 use std::mem;
-use std::ops::{Add, Mul, Div};
+use std::ops::{Add, Mul, Div, Sub, Neg};
 use std::ptr;
 use std::slice;
 
@@ -713,6 +717,420 @@ pub trait BaseMatrix<T>: Sized {
         forward_substitution(self, y)
     }
 
+    /// Solves an upper triangular linear system with many right-hand sides.
+    ///
+    /// Given a matrix `U`, which is upper triangular, and a matrix `B`, this function
+    /// returns `X` such that `UX = B`. This uses a blocked algorithm, where most of the
+    /// work is done by matrix multiplication.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let u = Matrix::new(2, 2, vec![1.0, 2.0, 0.0, 1.0]);
+    /// let b = Matrix::new(2, 2, vec![3.0, 2.0, 1.0, 1.0]);
+    ///
+    /// let x = u.solve_u_triangular_matrix(&b).expect("A solution should exist!");
+    /// assert_eq!(*x.data(), vec![1.0, 0.0, 1.0, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The row count of `B` and the matrix column count are not equal.
+    /// - Matrix is not square.
+    /// - Matrix is not upper triangular.
+    ///
+    /// # Failures
+    ///
+    /// Fails if there is no valid solution to the system (matrix is singular).
+    fn solve_u_triangular_matrix<M>(&self, b: &M) -> Result<Matrix<T>, Error>
+        where T: Any + Float,
+              M: BaseMatrix<T>
+    {
+        assert!(self.cols() == b.rows(),
+                "Matrix row count {0} != {1} Matrix column count.",
+                b.rows(),
+                self.cols());
+        assert!(self.rows() == self.cols(), "Matrix must be square.");
+
+        // Make sure we are upper triangular.
+        for (row_idx, row) in self.iter_rows().enumerate() {
+            if row.iter().take(row_idx).any(|data| data != &T::zero()) {
+                panic!("Matrix is not upper triangular");
+            }
+        }
+
+        try!(check_triangular_diagonal(self));
+
+        let mut x = b.as_slice().into_matrix();
+        back_substitution_in_place(self, &mut x);
+        Ok(x)
+    }
+
+    /// Solves a lower triangular linear system with many right-hand sides.
+    ///
+    /// Given a matrix `L`, which is lower triangular, and a matrix `B`, this function
+    /// returns `X` such that `LX = B`. This uses a blocked algorithm, where most of the
+    /// work is done by matrix multiplication.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    ///
+    /// let l = Matrix::new(2, 2, vec![1.0, 0.0, 2.0, 1.0]);
+    /// let b = Matrix::new(2, 2, vec![1.0, 0.0, 3.0, 1.0]);
+    ///
+    /// let x = l.solve_l_triangular_matrix(&b).expect("A solution should exist!");
+    /// assert_eq!(*x.data(), vec![1.0, 0.0, 1.0, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The row count of `B` and the matrix column count are not equal.
+    /// - Matrix is not square.
+    /// - Matrix is not lower triangular.
+    ///
+    /// # Failures
+    ///
+    /// Fails if there is no valid solution to the system (matrix is singular).
+    fn solve_l_triangular_matrix<M>(&self, b: &M) -> Result<Matrix<T>, Error>
+        where T: Any + Float,
+              M: BaseMatrix<T>
+    {
+        assert!(self.cols() == b.rows(),
+                "Matrix row count {0} != {1} Matrix column count.",
+                b.rows(),
+                self.cols());
+        assert!(self.rows() == self.cols(), "Matrix must be square.");
+
+        // Make sure we are lower triangular.
+        for (row_idx, row) in self.iter_rows().enumerate() {
+            if row.iter().skip(row_idx + 1).any(|data| data != &T::zero()) {
+                panic!("Matrix is not lower triangular.");
+            }
+        }
+
+        try!(check_triangular_diagonal(self));
+
+        let mut x = b.as_slice().into_matrix();
+        forward_substitution_in_place(self, &mut x, false);
+        Ok(x)
+    }
+
+    /// Solves the transpose of an upper triangular linear system.
+    ///
+    /// Given a matrix `U`, which is upper triangular, and a vector `y`, this function
+    /// returns `x` such that `Uᵀx = y`. The transpose of `U` is never formed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let u = Matrix::new(2, 2, vec![1.0, 2.0, 0.0, 1.0]);
+    /// let y = Vector::new(vec![1.0, 3.0]);
+    ///
+    /// let x = u.solve_u_triangular_transpose(y).expect("A solution should exist!");
+    /// assert_eq!(x, Vector::new(vec![1.0, 1.0]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - Vector size and matrix column count are not equal.
+    /// - Matrix is not square.
+    /// - Matrix is not upper triangular.
+    ///
+    /// # Failures
+    ///
+    /// Fails if there is no valid solution to the system (matrix is singular).
+    fn solve_u_triangular_transpose(&self, y: Vector<T>) -> Result<Vector<T>, Error>
+        where T: Any + Float
+    {
+        assert!(self.cols() == y.size(),
+                "Vector size {0} != {1} Matrix column count.",
+                y.size(),
+                self.cols());
+        assert!(self.rows() == self.cols(), "Matrix must be square.");
+
+        // Make sure we are upper triangular.
+        for (row_idx, row) in self.iter_rows().enumerate() {
+            if row.iter().take(row_idx).any(|data| data != &T::zero()) {
+                panic!("Matrix is not upper triangular");
+            }
+        }
+
+        try!(check_triangular_diagonal(self));
+
+        let mut x = Matrix::new(y.size(), 1, y.into_vec());
+        back_substitution_transpose_in_place(self, &mut x);
+        Ok(Vector::new(x.into_vec()))
+    }
+
+    /// Solves the transpose of a lower triangular linear system.
+    ///
+    /// Given a matrix `L`, which is lower triangular, and a vector `y`, this function
+    /// returns `x` such that `Lᵀx = y`. The transpose of `L` is never formed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrix};
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let l = Matrix::new(2, 2, vec![1.0, 0.0, 2.0, 1.0]);
+    /// let y = Vector::new(vec![3.0, 1.0]);
+    ///
+    /// let x = l.solve_l_triangular_transpose(y).expect("A solution should exist!");
+    /// assert_eq!(x, Vector::new(vec![1.0, 1.0]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - Vector size and matrix column count are not equal.
+    /// - Matrix is not square.
+    /// - Matrix is not lower triangular.
+    ///
+    /// # Failures
+    ///
+    /// Fails if there is no valid solution to the system (matrix is singular).
+    fn solve_l_triangular_transpose(&self, y: Vector<T>) -> Result<Vector<T>, Error>
+        where T: Any + Float
+    {
+        assert!(self.cols() == y.size(),
+                "Vector size {0} != {1} Matrix column count.",
+                y.size(),
+                self.cols());
+        assert!(self.rows() == self.cols(), "Matrix must be square.");
+
+        // Make sure we are lower triangular.
+        for (row_idx, row) in self.iter_rows().enumerate() {
+            if row.iter().skip(row_idx + 1).any(|data| data != &T::zero()) {
+                panic!("Matrix is not lower triangular.");
+            }
+        }
+
+        try!(check_triangular_diagonal(self));
+
+        let mut x = Matrix::new(y.size(), 1, y.into_vec());
+        forward_substitution_transpose_in_place(self, &mut x, false);
+        Ok(Vector::new(x.into_vec()))
+    }
+
+    /// Cholesky decomposition of a copy of the matrix.
+    ///
+    /// Returns the lower triangular factor L of a positive definite
+    /// matrix, as in `Matrix::cholesky`. Only the lower triangle of the
+    /// matrix is used. The matrix is copied once and factorized with
+    /// `cholesky_in_place`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSlice, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 3, vec![9.0, 9.0, 9.0,
+    ///                                9.0, 4.0, 2.0,
+    ///                                9.0, 2.0, 5.0]);
+    /// let block = MatrixSlice::from_matrix(&a, [1, 1], 2, 2);
+    ///
+    /// let l = block.cholesky_cloned().unwrap();
+    /// assert_eq!(*l.data(), vec![2.0, 0.0, 1.0, 2.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix is not positive definite.
+    fn cholesky_cloned(&self) -> Result<Matrix<T>, Error>
+        where T: Any + Float
+    {
+        let mut l = self.as_slice().into_matrix();
+        try!(l.as_mut_slice().cholesky_in_place());
+        Ok(l)
+    }
+
+    /// LUP decomposition of a copy of the matrix.
+    ///
+    /// Returns L, U, and P respectively, such that PA = LU, as in
+    /// `Matrix::lup_decomp`. The matrix is copied once and factorized
+    /// with `lup_decomp_in_place`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSlice, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 3, vec![1.0, 2.0, 0.0,
+    ///                                0.0, 3.0, 4.0,
+    ///                                5.0, 1.0, 2.0]);
+    /// let block = MatrixSlice::from_matrix(&a, [1, 1], 2, 2);
+    ///
+    /// let (l, u, p) = block.lup_decomp_cloned().unwrap();
+    /// assert_eq!(&l * &u, &p * block.into_matrix());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - Matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix cannot be LUP decomposed.
+    fn lup_decomp_cloned(&self) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error>
+        where T: Any + Copy + One + Zero + Neg<Output = T> + Add<T, Output = T> +
+                 Mul<T, Output = T> + Sub<T, Output = T> + Div<T, Output = T> + PartialOrd
+    {
+        let n = self.cols();
+        assert!(self.rows() == n, "Matrix must be square for LUP decomposition.");
+
+        let mut u = self.as_slice().into_matrix();
+        let perm = try!(u.as_mut_slice().lup_decomp_in_place());
+
+        // Split the combined storage into L and U.
+        let mut l = Matrix::<T>::identity(n);
+        for i in 1..n {
+            l.data[i * n..i * n + i].copy_from_slice(&u.data[i * n..i * n + i]);
+            for x in &mut u.data[i * n..i * n + i] {
+                *x = T::zero();
+            }
+        }
+
+        let mut p = Matrix::<T>::zeros(n, n);
+        for (i, &j) in perm.iter().enumerate() {
+            p.data[i * n + j] = T::one();
+        }
+
+        Ok((l, u, p))
+    }
+
+    /// QR decomposition of a copy of the matrix.
+    ///
+    /// Returns the tuple (Q,R), as in `Matrix::qr_decomp`. The matrix is
+    /// copied once and factorized with `qr_decomp_in_place`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSlice, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 3, vec![1.0, 0.5, 0.5,
+    ///                                0.5, 1.0, 0.5,
+    ///                                0.5, 0.5, 1.0]);
+    /// let block = MatrixSlice::from_matrix(&a, [0, 0], 3, 2);
+    ///
+    /// let (q, r) = block.qr_decomp_cloned().unwrap();
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - Cannot compute the QR decomposition.
+    fn qr_decomp_cloned(&self) -> Result<(Matrix<T>, Matrix<T>), Error>
+        where T: Any + Float
+    {
+        self.as_slice().into_matrix().qr_decomp()
+    }
+
+    /// Singular Value Decomposition of a copy of the matrix.
+    ///
+    /// Computes the SVD using the Golub-Reinsch algorithm, returning
+    /// the tuple (Σ, U, V), as in `Matrix::svd`. The matrix is copied once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSlice, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 3, vec![3.0, 0.0, 0.0,
+    ///                                0.0, 2.0, 0.0,
+    ///                                0.0, 0.0, 1.0]);
+    /// let block = MatrixSlice::from_matrix(&a, [1, 1], 2, 2);
+    ///
+    /// let (s, _, _) = block.svd_cloned().unwrap();
+    /// assert_eq!(*s.diag().data(), vec![2.0, 1.0]);
+    /// ```
+    ///
+    /// # Failures
+    ///
+    /// - The matrix cannot be reduced to bidiagonal form.
+    fn svd_cloned(&self) -> Result<(Matrix<T>, Matrix<T>, Matrix<T>), Error>
+        where T: Any + Float + Signed + MachineEpsilon
+    {
+        self.as_slice().into_matrix().svd()
+    }
+
+    /// Eigenvalues of a copy of a square matrix.
+    ///
+    /// Returns a Vec of eigenvalues, as in `Matrix::eigenvalues`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSlice, BaseMatrix};
+    ///
+    /// let a = Matrix::new(3, 3, vec![1.0f64, 0.0, 0.0,
+    ///                                0.0, 2.0, 1.0,
+    ///                                0.0, 0.0, 3.0]);
+    /// let block = MatrixSlice::from_matrix(&a, [1, 1], 2, 2);
+    ///
+    /// let mut e = block.eigenvalues_cloned().unwrap();
+    /// e.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// assert!((e[0] - 2.0).abs() < 1e-12 && (e[1] - 3.0).abs() < 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Eigenvalues cannot be computed.
+    fn eigenvalues_cloned(&self) -> Result<Vec<T>, Error>
+        where T: Any + Float + Signed + MachineEpsilon
+    {
+        self.as_slice().into_matrix().eigenvalues()
+    }
+
+    /// Solves the equation `Ax = y` using a copy of the matrix.
+    ///
+    /// Requires a Vector `y` as input, as in `Matrix::solve`. The matrix
+    /// is copied once and factorized with `PartialPivLu`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, MatrixSlice, BaseMatrix};
+    /// use rulinalg::vector::Vector;
+    ///
+    /// let a = Matrix::new(3, 3, vec![9.0, 9.0, 9.0,
+    ///                                9.0, 2.0, 0.0,
+    ///                                9.0, 0.0, 4.0]);
+    /// let block = MatrixSlice::from_matrix(&a, [1, 1], 2, 2);
+    ///
+    /// let x = block.solve_cloned(Vector::new(vec![2.0, 8.0])).unwrap();
+    /// assert_eq!(x, Vector::new(vec![1.0, 2.0]));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix column count and vector size are different.
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - The matrix cannot be decomposed into an LUP form to solve.
+    /// - There is no valid solution as the matrix is singular.
+    fn solve_cloned(&self, y: Vector<T>) -> Result<Vector<T>, Error>
+        where T: Any + Float
+    {
+        PartialPivLu::decompose(self.as_slice().into_matrix()).solve(y)
+    }
+
     /// Split the matrix at the specified axis returning two `MatrixSlice`s.
     ///
     /// # Examples
@@ -1119,6 +1537,99 @@ pub trait BaseMatrixMut<T>: BaseMatrix<T> {
                                            rows, cols, self.row_stride())
         }
     }
+
+    /// Computes the Cholesky decomposition of a positive definite
+    /// matrix in place.
+    ///
+    /// On success the matrix is overwritten with the lower triangular
+    /// factor L. Only the lower triangle is used. See
+    /// `MatrixSliceMut::cholesky_in_place`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrixMut};
+    ///
+    /// let mut a = Matrix::new(3, 3, vec![9.0, 9.0, 9.0,
+    ///                                    9.0, 4.0, 2.0,
+    ///                                    9.0, 2.0, 5.0]);
+    ///
+    /// a.sub_slice_mut([1, 1], 2, 2).cholesky_in_place().unwrap();
+    /// assert_eq!(*a.data(), vec![9.0, 9.0, 9.0,
+    ///                            9.0, 2.0, 0.0,
+    ///                            9.0, 1.0, 2.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix is not positive definite, the contents of the matrix are unspecified.
+    fn cholesky_in_place(&mut self) -> Result<(), Error>
+        where T: Any + Float
+    {
+        MatrixSliceMut::cholesky_in_place(&mut self.as_mut_slice())
+    }
+
+    /// Computes the QR decomposition of the matrix in place.
+    ///
+    /// R is stored in the upper triangle and the Householder vectors
+    /// below the diagonal, and the scalar factors of the reflections are
+    /// returned. See `MatrixSliceMut::qr_decomp_in_place`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrixMut};
+    ///
+    /// let mut a = Matrix::new(3, 2, vec![3.0f64, 1.0,
+    ///                                    4.0, 2.0,
+    ///                                    0.0, 5.0]);
+    ///
+    /// let tau = a.qr_decomp_in_place();
+    /// assert_eq!(tau.len(), 2);
+    /// assert!((a[[0, 0]].abs() - 5.0).abs() < 1e-12);
+    /// ```
+    fn qr_decomp_in_place(&mut self) -> Vec<T>
+        where T: Any + Float
+    {
+        MatrixSliceMut::qr_decomp_in_place(&mut self.as_mut_slice())
+    }
+
+    /// Computes the LUP decomposition of the matrix in place.
+    ///
+    /// On success U is stored in the upper triangle and the unit lower
+    /// triangular L strictly below the diagonal, and the permutation is
+    /// returned. See `MatrixSliceMut::lup_decomp_in_place`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rulinalg::matrix::{Matrix, BaseMatrixMut};
+    ///
+    /// let mut a = Matrix::new(2, 2, vec![1.0, 2.0,
+    ///                                    4.0, 4.0]);
+    ///
+    /// let perm = a.lup_decomp_in_place().unwrap();
+    /// assert_eq!(perm, vec![1, 0]);
+    /// assert_eq!(*a.data(), vec![4.0, 4.0, 0.25, 1.0]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - The matrix is not square.
+    ///
+    /// # Failures
+    ///
+    /// - Matrix cannot be LUP decomposed, the contents of the matrix are unspecified.
+    fn lup_decomp_in_place(&mut self) -> Result<Vec<usize>, Error>
+        where T: Any + Copy + One + Zero + Neg<Output = T> + Add<T, Output = T> +
+                 Mul<T, Output = T> + Sub<T, Output = T> + Div<T, Output = T> + PartialOrd
+    {
+        MatrixSliceMut::lup_decomp_in_place(&mut self.as_mut_slice())
+    }
 }
 
 impl<T> BaseMatrix<T> for Matrix<T> {
@@ -1756,4 +2267,86 @@ mod tests {
         assert_eq!(a[[3, 1]], c[[1, 3]]);
         assert_eq!(a[[4, 1]], c[[1, 4]]);
     }
+
+    #[test]
+    fn test_solve_triangular_matrix() {
+        use vector::Vector;
+
+        // Large enough to use several blocks.
+        let n = 150;
+        let u = Matrix::from_fn(n, n, |j, i| {
+            if j < i {
+                0.0
+            } else if j == i {
+                2.0 + (i % 7) as f64
+            } else {
+                ((i + j) % 5) as f64 / 10.0 - 0.2
+            }
+        });
+        let l = u.transpose();
+        let b = Matrix::from_fn(n, 4, |j, i| ((i * 3 + j * 7) % 11) as f64 - 5.0);
+
+        let x_u = u.solve_u_triangular_matrix(&b).unwrap();
+        let x_l = l.solve_l_triangular_matrix(&b).unwrap();
+
+        for j in 0..4 {
+            let column = Vector::new((0..n).map(|i| b[[i, j]]).collect::<Vec<_>>());
+            let expected_u = u.solve_u_triangular(column.clone()).unwrap();
+            let expected_l = l.solve_l_triangular(column).unwrap();
+
+            assert!((0..n).all(|i| (x_u[[i, j]] - expected_u[i]).abs() < 1e-10));
+            assert!((0..n).all(|i| (x_l[[i, j]] - expected_l[i]).abs() < 1e-10));
+        }
+
+        // Right-hand sides can be slices.
+        let slice = MatrixSlice::from_matrix(&b, [0, 1], n, 2);
+        let x_slice = u.solve_u_triangular_matrix(&slice).unwrap();
+        assert_eq!(x_slice, x_u.select_cols(&[1, 2]));
+    }
+
+    #[test]
+    fn test_solve_triangular_transpose() {
+        use vector::Vector;
+
+        // Large enough to use several blocks.
+        let n = 150;
+        let u = Matrix::from_fn(n, n, |j, i| {
+            if j < i {
+                0.0
+            } else if j == i {
+                2.0 + (i % 7) as f64
+            } else {
+                ((i + j) % 5) as f64 / 10.0 - 0.2
+            }
+        });
+        let l = Matrix::from_fn(n, n, |j, i| if j > i { 0.0 } else { u[[j, i]] + 0.1 });
+        let y = Vector::new((0..n).map(|i| ((i * 3) % 11) as f64 - 5.0).collect::<Vec<_>>());
+
+        let x_u = u.solve_u_triangular_transpose(y.clone()).unwrap();
+        let expected_u = u.transpose().solve_l_triangular(y.clone()).unwrap();
+        assert!((0..n).all(|i| (x_u[i] - expected_u[i]).abs() < 1e-10));
+
+        let x_l = l.solve_l_triangular_transpose(y.clone()).unwrap();
+        let expected_l = l.transpose().solve_u_triangular(y).unwrap();
+        assert!((0..n).all(|i| (x_l[i] - expected_l[i]).abs() < 1e-10));
+
+        let singular = Matrix::new(2, 2, vec![1.0, 2.0, 0.0, 0.0]);
+        assert!(singular.solve_u_triangular_transpose(Vector::new(vec![1.0, 1.0])).is_err());
+    }
+
+    #[test]
+    fn test_solve_triangular_matrix_singular() {
+        let u = Matrix::new(2, 2, vec![1.0, 2.0, 0.0, 0.0]);
+        let b = Matrix::new(2, 1, vec![1.0, 1.0]);
+
+        assert!(u.solve_u_triangular_matrix(&b).is_err());
+        assert!(u.transpose().solve_l_triangular_matrix(&b).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_solve_l_triangular_matrix_not_triangular() {
+        let l = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0]);
+        let _ = l.solve_l_triangular_matrix(&Matrix::new(2, 1, vec![1.0, 1.0]));
+    }
 }